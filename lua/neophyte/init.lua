@@ -14,9 +14,13 @@ local M = {}
 ---@field value number The font variation value.
 
 ---@class FontFull
----@field name string The font name. If you don't need features or variations, consider using the string shorthand of Font.
+---@field name string The font name, or a path to a font file or directory. If you don't need features or variations, consider using the string shorthand of Font.
 ---@field features? FontFeature[]
 ---@field variations? FontVariation[]
+---@field hinting? boolean Whether to hint glyph outlines. Defaults to true.
+---@field embolden? number Amount to thicken glyph outlines by in pixels. Defaults to 0.
+---@field line_height? number Multiplier of the line height given by the font metrics. Only the first font affects the cell size. Defaults to 1.
+---@field baseline_offset? number Amount to raise glyphs from this font in pixels, useful for aligning fallback fonts. Defaults to 0.
 
 ---@alias Font string | FontFull
 
@@ -32,13 +36,85 @@ local M = {}
 ---@field b number
 ---@field a number
 
+---@class PointerConfig
+---@field text? string The pointer over text, as a CSS cursor name such as 'text' or 'default'. Defaults to 'text'.
+---@field chrome? string The pointer over statuslines, winbars, and floating window borders. Defaults to 'default'.
+---@field vertical_separator? string The pointer over separators between vertical splits. Defaults to 'col-resize'.
+---@field horizontal_separator? string The pointer over statuslines between horizontal splits. Defaults to 'row-resize'.
+---@field hide_while_typing? boolean Whether to hide the pointer while typing until the mouse moves. Defaults to true.
+
+---@alias EasingKind 'ease_out_quad' | 'linear' | 'ease_out_cubic' | 'ease_out_expo' | 'spring'
+
+---@class EasingFull
+---@field kind EasingKind
+---@field stiffness? number How quickly a spring settles. Only used by 'spring'. Defaults to 100.
+
+---@alias Easing EasingKind | EasingFull
+
+---@class EasingConfig
+---@field scroll? Easing Defaults to 'ease_out_quad'.
+---@field cursor? Easing Defaults to 'ease_out_quad'.
+---@field cmdline_cursor? Easing Defaults to 'ease_out_quad'.
+
+---@alias ParticleStyle 'railgun' | 'torpedo' | 'pixie_dust'
+
+---@class CursorEffectsConfig
+---@field trail? boolean Whether to leave a fading trail behind the moving cursor. Defaults to false.
+---@field particles? ParticleStyle The particles to emit when the cursor jumps or enters insert mode. Defaults to none.
+
+---@alias WindowTransition 'none' | 'fade' | 'scale'
+
+---@class WindowAnimationConfig
+---@field movement? boolean Whether floating windows move smoothly to new positions, following the cursor speed and easing. Defaults to false.
+---@field open? WindowTransition How floating windows appear. 'scale' fades while growing to full size. Defaults to 'none'.
+---@field close? WindowTransition How floating windows disappear. 'scale' fades while shrinking. Defaults to 'none'.
+
+---@class ShadowConfig
+---@field blur? number How far the shadow fades out past its edges in pixels. Defaults to 16.
+---@field x? number Pixels to move the shadow right. Defaults to 0.
+---@field y? number Pixels to move the shadow down. Defaults to 4.
+---@field color? integer The shadow color as 0xRRGGBB. Defaults to black.
+---@field opacity? number The opacity of the shadow from 0 to 1. Defaults to 0.5.
+
+---@class FloatStyle
+---@field corner_radius? number The radius of the rounded corners in pixels. Defaults to 0.
+---@field shadow? ShadowConfig | boolean A drop shadow beneath the window, or true for the default shadow. Defaults to none.
+
+---@class DimInactiveConfig
+---@field enabled? boolean Whether normal windows without the cursor are dimmed. Floating windows are never dimmed. Defaults to false.
+---@field amount? number How much to darken and desaturate inactive windows from 0 to 1. Defaults to 0.3.
+
+---@class Padding
+---@field top? integer Defaults to 0.
+---@field right? integer Defaults to 0.
+---@field bottom? integer Defaults to 0.
+---@field left? integer Defaults to 0.
+
+---@class LayoutConfig
+---@field padding? Padding | integer Pixels to leave around the grid on each side, or one number for every side. Defaults to 0.
+---@field alignment? 'center' | 'top_left' Where the grid goes when the window is not a whole number of cells. Defaults to 'center'.
+---@field fill? integer The color of the space around the grid as 0xRRGGBB. Defaults to the background color.
+
 ---@class Config
+---@field font_dirs? string[]
 ---@field fonts? Font[]
 ---@field font_size? FontSize
 ---@field underline_offset? number
+---@field letter_spacing? number
 ---@field cursor_speed? number
+---@field cursor_effects? CursorEffectsConfig
 ---@field scroll_speed? number
+---@field easing? EasingConfig
+---@field window_animation? WindowAnimationConfig
+---@field float_style? FloatStyle
+---@field dim_inactive? DimInactiveConfig
+---@field layout? LayoutConfig
+---@field pixel_scroll? boolean Whether trackpad scrolling follows the fingers exactly rather than moving by whole lines. Defaults to false.
 ---@field bg_override? Color
+---@field pointer? PointerConfig
+---@field multiclick_interval? integer The longest time between clicks in milliseconds that counts as a double, triple, or quadruple click. Defaults to 500.
+---@field default_keybindings? boolean Whether to enable the default keybindings for fullscreen, copy, paste, and new windows, and on macOS, zoom. Defaults to true.
+---@field keybindings? { [string]: Keybinding }
 
 ---@alias motion 'still' | 'animating'
 
@@ -65,6 +141,10 @@ function M.setup(config)
     end
   })
 
+  if config.font_dirs ~= nil then
+    M.set_font_dirs(config.font_dirs)
+  end
+
   if config.fonts ~= nil then
     M.set_fonts(config.fonts)
   end
@@ -87,19 +167,69 @@ function M.setup(config)
     M.set_underline_offset(config.underline_offset)
   end
 
+  if config.letter_spacing ~= nil then
+    M.set_letter_spacing(config.letter_spacing)
+  end
+
   if config.cursor_speed ~= nil then
     M.set_cursor_speed(config.cursor_speed)
   end
 
+  if config.cursor_effects ~= nil then
+    M.set_cursor_effects(config.cursor_effects)
+  end
+
   if config.scroll_speed ~= nil then
     M.set_scroll_speed(config.scroll_speed)
   end
 
+  if config.easing ~= nil then
+    M.set_easing(config.easing)
+  end
+
+  if config.window_animation ~= nil then
+    M.set_window_animation(config.window_animation)
+  end
+
+  if config.float_style ~= nil then
+    M.set_float_style(config.float_style)
+  end
+
+  if config.dim_inactive ~= nil then
+    M.set_dim_inactive(config.dim_inactive)
+  end
+
+  if config.layout ~= nil then
+    M.set_layout(config.layout)
+  end
+
+  if config.pixel_scroll ~= nil then
+    M.set_pixel_scroll(config.pixel_scroll)
+  end
+
   if config.bg_override ~= nil then
     local bg = config.bg_override
     assert(bg)
     M.set_bg_override(bg.r, bg.g, bg.b, bg.a)
   end
+
+  if config.pointer ~= nil then
+    M.set_pointer(config.pointer)
+  end
+
+  if config.multiclick_interval ~= nil then
+    M.set_multiclick_interval(config.multiclick_interval)
+  end
+
+  if config.default_keybindings ~= nil then
+    M.set_default_keybindings(config.default_keybindings)
+  end
+
+  if config.keybindings ~= nil then
+    for keys, action in pairs(config.keybindings) do
+      M.map(keys, action)
+    end
+  end
 end
 
 ---Gets whether Neovim is running in Neophyte
@@ -139,6 +269,12 @@ function M.set_fonts(fonts)
   vim.rpcnotify(1, 'neophyte.set_fonts', fonts)
 end
 
+---Set additional directories to search for fonts by family name before falling back to system fonts. Affects fonts set afterward.
+---@param dirs string[]
+function M.set_font_dirs(dirs)
+  vim.rpcnotify(1, 'neophyte.set_font_dirs', dirs)
+end
+
 ---Set the offset of underlines from the font baseline
 ---@return number
 function M.get_underline_offset()
@@ -151,12 +287,51 @@ function M.set_underline_offset(offset)
   vim.rpcnotify(1, 'neophyte.set_underline_offset', { offset })
 end
 
+---Set the number of pixels to add between letters. Use the 'linespace' option to add space between lines.
+---@param spacing number
+function M.set_letter_spacing(spacing)
+  vim.rpcnotify(1, 'neophyte.set_letter_spacing', { spacing })
+end
+
+---Get the number of pixels added between letters
+---@return number
+function M.get_letter_spacing()
+  return vim.rpcrequest(1, 'neophyte.get_letter_spacing', {})
+end
+
 ---Get the names of loaded fonts
 ---@return string[]
 function M.get_fonts()
   return vim.rpcrequest(1, 'neophyte.get_fonts', {})
 end
 
+---@class SystemFontStyle
+---@field name string The style name given by the font, such as 'Bold Italic'
+---@field weight integer The font weight, such as 400 for regular or 700 for bold
+---@field italic boolean
+
+---@class SystemFont
+---@field name string The family name
+---@field styles SystemFontStyle[]
+---@field monospace boolean Whether the family is monospace
+
+---List the font families installed on the system. This can take a moment since every font file is read.
+---@return SystemFont[]
+function M.list_system_fonts()
+  return vim.rpcrequest(1, 'neophyte.list_system_fonts', {})
+end
+
+---Temporarily use the given font ahead of the current fonts, such as while browsing fonts in a picker. Use `end_font_preview` to restore the previous fonts or `set_fonts` to commit a choice.
+---@param font Font
+function M.preview_font(font)
+  vim.rpcnotify(1, 'neophyte.preview_font', { font })
+end
+
+---Restore the fonts that were in use before `preview_font` was called
+function M.end_font_preview()
+  vim.rpcnotify(1, 'neophyte.end_font_preview', {})
+end
+
 ---Set the cursor speed as a multiple of the base speed
 ---@param speed number
 function M.set_cursor_speed(speed)
@@ -169,6 +344,12 @@ function M.get_cursor_speed()
   return vim.rpcrequest(1, 'neophyte.get_cursor_speed', {})
 end
 
+---Set the trails and particles that follow the cursor. Unset fields use their defaults.
+---@param effects CursorEffectsConfig
+function M.set_cursor_effects(effects)
+  vim.rpcnotify(1, 'neophyte.set_cursor_effects', { effects })
+end
+
 ---Set the scroll speed as a multiple of the base speed
 ---@param speed number
 function M.set_scroll_speed(speed)
@@ -181,6 +362,114 @@ function M.get_scroll_speed()
   return vim.rpcrequest(1, 'neophyte.get_scroll_speed', {})
 end
 
+---Set the animation curves for scrolling and the cursors. Unset fields use their defaults. The speed settings still apply.
+---@param easing EasingConfig
+function M.set_easing(easing)
+  vim.rpcnotify(1, 'neophyte.set_easing', { easing })
+end
+
+---Set how floating windows move, appear, and disappear. Unset fields use their defaults.
+---@param animation WindowAnimationConfig
+function M.set_window_animation(animation)
+  vim.rpcnotify(1, 'neophyte.set_window_animation', { animation })
+end
+
+---@type DimInactiveConfig
+local dim_inactive = {}
+
+---Set how the windows the cursor isn't in are dimmed. Unset fields use their defaults.
+---@param config DimInactiveConfig
+function M.set_dim_inactive(config)
+  dim_inactive = vim.deepcopy(config)
+  vim.rpcnotify(1, 'neophyte.set_dim_inactive', { dim_inactive })
+end
+
+---Turn dimming of inactive windows on or off, keeping the amount from `set_dim_inactive`
+function M.toggle_dim_inactive()
+  dim_inactive.enabled = not dim_inactive.enabled
+  vim.rpcnotify(1, 'neophyte.set_dim_inactive', { dim_inactive })
+end
+
+---Move a window by a number of pixels on top of its position from Neovim. This only changes where the window is drawn, not where it is clicked.
+---@param win integer The window handle, or 0 for the current window
+---@param dx number Pixels to the right
+---@param dy number Pixels down
+function M.set_window_offset(win, dx, dy)
+  if win == 0 then
+    win = vim.api.nvim_get_current_win()
+  end
+  vim.rpcnotify(1, 'neophyte.set_window_offset', { win, dx, dy })
+end
+
+---Set the opacity of a window, showing the windows below it
+---@param win integer The window handle, or 0 for the current window
+---@param opacity number From 0 for invisible to 1 for opaque
+function M.set_window_opacity(win, opacity)
+  if win == 0 then
+    win = vim.api.nvim_get_current_win()
+  end
+  vim.rpcnotify(1, 'neophyte.set_window_opacity', { win, opacity })
+end
+
+---Set the size a window is drawn at, scaling from its center. This only changes how the window is drawn, not where it is clicked.
+---@param win integer The window handle, or 0 for the current window
+---@param scale number The size relative to the actual size, where 1 is the actual size
+function M.set_window_scale(win, scale)
+  if win == 0 then
+    win = vim.api.nvim_get_current_win()
+  end
+  vim.rpcnotify(1, 'neophyte.set_window_scale', { win, scale })
+end
+
+---Set the rounded corners and drop shadow of floating windows. Unset fields use their defaults.
+---@param style FloatStyle
+function M.set_float_style(style)
+  vim.rpcnotify(1, 'neophyte.set_float_style', { style })
+end
+
+---Set the rounded corners and drop shadow of a floating window in place of the style from `set_float_style`. Unset fields use their defaults.
+---@param win integer The window handle, or 0 for the current window
+---@param style FloatStyle
+function M.set_window_style(win, style)
+  if win == 0 then
+    win = vim.api.nvim_get_current_win()
+  end
+  vim.rpcnotify(1, 'neophyte.set_window_style', { win, style })
+end
+
+---Make a floating window use the style from `set_float_style` again
+---@param win integer The window handle, or 0 for the current window
+function M.unset_window_style(win)
+  if win == 0 then
+    win = vim.api.nvim_get_current_win()
+  end
+  vim.rpcnotify(1, 'neophyte.unset_window_style', { win })
+end
+
+---Set whether trackpad scrolling moves the content by pixels to follow the fingers, coasting after they lift, rather than moving by whole lines
+---@param enabled boolean
+function M.set_pixel_scroll(enabled)
+  vim.rpcnotify(1, 'neophyte.set_pixel_scroll', { enabled })
+end
+
+---Set the mouse pointer icons and behavior. Unset fields use their defaults.
+---@param pointer PointerConfig
+function M.set_pointer(pointer)
+  vim.rpcnotify(1, 'neophyte.set_pointer', { pointer })
+end
+
+---Set the longest time between clicks that counts as a double, triple, or quadruple click
+---@param ms integer The interval in milliseconds
+function M.set_multiclick_interval(ms)
+  vim.rpcnotify(1, 'neophyte.set_multiclick_interval', { ms })
+end
+
+---Get the longest time between clicks that counts as a multiclick
+---@return integer
+function M.get_multiclick_interval()
+  return vim.rpcrequest(1, 'neophyte.get_multiclick_interval', {})
+end
+
 ---Sets the window to fullscreen or windowed
 ---@param is_fullscreen boolean
 function M.set_fullscreen(is_fullscreen)
@@ -205,6 +494,29 @@ function M.unset_render_size()
   vim.rpcnotify(1, 'neophyte.unset_render_size', {})
 end
 
+---@class PostProcessOptions
+---@field animate? boolean Whether to redraw continuously for shaders that use `uniforms.time`. Defaults to false.
+
+---Run a WGSL shader over each frame. The file defines `fn fs_main(in: VertexOutput) -> @location(0) vec4<f32>`, where `in.uv` goes from 0 at the top left to 1 at the bottom right. It can sample the frame with `textureSampleLevel(frame, frame_sampler, in.uv, 0.0)` and read `uniforms.resolution`, `uniforms.cursor` (the cursor center in pixels), `uniforms.time` (seconds since loading), and `uniforms.mode` (one of MODE_NORMAL, MODE_INSERT, MODE_VISUAL, MODE_REPLACE, MODE_CMDLINE, or MODE_OTHER). The shader is reloaded when the file changes, and compilation errors are shown with vim.notify.
+---@param path string
+---@param opts? PostProcessOptions
+function M.set_post_process(path, opts)
+  opts = opts or {}
+  path = vim.fn.fnamemodify(vim.fn.expand(path), ':p')
+  vim.rpcnotify(1, 'neophyte.set_post_process', { path, opts.animate or false })
+end
+
+---Stop running the shader from `set_post_process`
+function M.unset_post_process()
+  vim.rpcnotify(1, 'neophyte.unset_post_process', {})
+end
+
+---Set the padding around the grid, how it is aligned in the window, and the color of the space around it. Unset fields use their defaults.
+---@param layout LayoutConfig
+function M.set_layout(layout)
+  vim.rpcnotify(1, 'neophyte.set_layout', { layout })
+end
+
 ---Gets the current size of the render target
 ---@return { width: integer, height: integer }
 function M.get_render_size()
@@ -260,6 +572,128 @@ function M.receive_raw_input(input)
   end
 end
 
+---@alias GuiAction 'toggle_fullscreen' | 'zoom_in' | 'zoom_out' | 'zoom_reset' | 'copy' | 'paste' | 'new_window'
+
+---@alias Keybinding GuiAction | string | fun(): nil
+
+local gui_actions = {
+  toggle_fullscreen = true,
+  zoom_in = true,
+  zoom_out = true,
+  zoom_reset = true,
+  copy = true,
+  paste = true,
+  new_window = true,
+}
+
+---@type { [string]: fun(): nil }
+local keybinding_callbacks = {}
+
+---Handle keys in Neophyte before they are sent to Neovim. Keybindings take precedence over Neovim mappings.
+---@param keys string The keys in the notation from the `keycodes` section of Neovim's documentation, such as '<C-S-v>'
+---@param action Keybinding | nil A built-in action, keys to send to Neovim instead, a function to call, or `nil` to remove the binding. Removing the binding of a default keybinding makes the keys go to Neovim as usual.
+function M.map(keys, action)
+  keybinding_callbacks[keys] = nil
+  local binding
+  if type(action) == 'function' then
+    keybinding_callbacks[keys] = action
+    binding = { lua = keys }
+  elseif type(action) == 'string' and gui_actions[action] then
+    binding = { action = action }
+  elseif type(action) == 'string' then
+    binding = { input = action }
+  end
+  vim.rpcnotify(1, 'neophyte.map', { keys, binding })
+end
+
+---Enables or disables the default keybindings. Bindings set with `map` are unaffected.
+---@param enabled boolean
+function M.set_default_keybindings(enabled)
+  vim.rpcnotify(1, 'neophyte.set_default_keybindings', { enabled })
+end
+
+---Calls the function bound to the given keys. This should only be called by Neophyte.
+---@param keys string The keys as given to `map`
+---@private
+function M.receive_keybinding(keys)
+  local callback = keybinding_callbacks[keys]
+  if callback ~= nil then
+    callback()
+  end
+end
+
+---Yanks the visual selection to the system clipboard. This should only be called by Neophyte.
+---@private
+function M.copy()
+  local mode = vim.api.nvim_get_mode().mode
+  if mode:match('^[vV\22]') then
+    vim.api.nvim_feedkeys('"+y', 'n', false)
+  end
+end
+
+---Pastes from the system clipboard. This should only be called by Neophyte.
+---@private
+function M.paste()
+  vim.paste(vim.fn.getreg('+', 1, true), -1)
+end
+
+---@class DropTarget
+---@field grid integer The grid under the pointer
+---@field row integer The row under the pointer, relative to the grid
+---@field col integer The column under the pointer, relative to the grid
+---@field win? integer The window containing the grid, if any
+
+---@alias DropHandler fun(paths: string[], target: DropTarget | nil): nil
+
+---@type DropHandler | nil
+local drop_handler = nil
+
+---Sets a function to handle files dropped on the window instead of opening them with `:drop`. The target is `nil` when the files are not dropped over a grid.
+---@param handler DropHandler | nil The function that will receive dropped files or `nil` to restore the default behavior
+function M.set_drop_handler(handler)
+  drop_handler = handler
+  if handler == nil then
+    vim.rpcnotify(1, 'neophyte.disable_drop_handler', {})
+  else
+    vim.rpcnotify(1, 'neophyte.enable_drop_handler', {})
+  end
+end
+
+---Sends the dropped files to the handler registered by `set_drop_handler`. This should only be called by Neophyte.
+---@param paths string[] The paths of the dropped files
+---@param target DropTarget | nil The cell under the pointer
+---@private
+function M.receive_drop(paths, target)
+  if drop_handler ~= nil then
+    drop_handler(paths, target)
+  end
+end
+
+---@alias LinkHandler fun(url: string): nil
+
+---@type LinkHandler | nil
+local link_handler = nil
+
+---Sets a function to handle hyperlinks opened with Ctrl+click instead of opening them with the system's default application. Without a handler, only http, https, and mailto links are opened.
+---@param handler LinkHandler | nil The function that will receive clicked links or `nil` to restore the default behavior
+function M.set_link_handler(handler)
+  link_handler = handler
+  if handler == nil then
+    vim.rpcnotify(1, 'neophyte.disable_link_handler', {})
+  else
+    vim.rpcnotify(1, 'neophyte.enable_link_handler', {})
+  end
+end
+
+---Sends the clicked link to the handler registered by `set_link_handler`. This should only be called by Neophyte.
+---@param url string The link target
+---@private
+function M.receive_link(url)
+  if link_handler ~= nil then
+    link_handler(url)
+  end
+end
+
 ---@alias FrameHandler fun(frame_number: integer): nil
 
 ---@type { [number]: FrameHandler }
@@ -9,6 +9,9 @@ pub struct HlAttrDefine {
     pub id: u32,
     /// Highlights in RGB format
     pub rgb_attr: Attributes,
+    /// A hyperlink target for text with this highlight, from the url
+    /// attribute
+    pub url: Option<String>,
     /// Highlights in terminal 256-color codes
     pub cterm_attr: Attributes,
     /// A semantic description of the highlights active in a cell. Ordered by
@@ -19,9 +22,17 @@ pub struct HlAttrDefine {
 impl Parse for HlAttrDefine {
     fn parse(value: Value) -> Option<Self> {
         let mut iter = Values::new(value)?;
+        let id = iter.next()?;
+        let rgb_attr: Value = iter.next()?;
+        let url = rgb_attr.as_map().and_then(|map| {
+            map.iter()
+                .find(|(k, _)| k.as_str() == Some("url"))
+                .and_then(|(_, v)| Some(v.as_str()?.to_string()))
+        });
         Some(Self {
-            id: iter.next()?,
-            rgb_attr: iter.next()?,
+            id,
+            rgb_attr: Attributes::parse(rgb_attr)?,
+            url,
             cterm_attr: iter.next()?,
             info: iter.next()?,
         })
@@ -33,7 +44,7 @@ impl Parse for HlAttrDefine {
 
 /// Attributes of a highlight attribute definition. Colors may be given in RGB
 /// or terminal 256-color.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Default)]
 pub struct Attributes {
     /// foreground color.
     pub foreground: Option<Rgb>,
@@ -44,17 +55,6 @@ pub struct Attributes {
     packed: u16,
 }
 
-impl Default for Attributes {
-    fn default() -> Self {
-        Self {
-            foreground: None,
-            background: None,
-            special: None,
-            packed: 100 << Self::BLEND_TRAILING,
-        }
-    }
-}
-
 #[rustfmt::skip]
 impl Attributes {
     const REVERSE:       u16 = 0b0000000000000001;
@@ -120,7 +120,7 @@ impl Attributes {
     /// Blend level (0-100). Could be used by UIs to support blending floating
     /// windows to the background or to signal a transparent cursor
     pub fn blend(&self) -> f32 {
-        let percentage = (self.packed & Self::BLEND_MASK >> Self::BLEND_TRAILING) as u8;
+        let percentage = ((self.packed & Self::BLEND_MASK) >> Self::BLEND_TRAILING).min(100);
         f32::from(100 - percentage) / 100.
     }
 
@@ -150,7 +150,7 @@ impl Parse for Attributes {
                 "underdotted" => out.maybe_set(v, Self::UNDERDOTTED)?,
                 "underdashed" => out.maybe_set(v, Self::UNDERDASHED)?,
                 "blend" => {
-                    let blend = u16::parse(v)?;
+                    let blend = u16::parse(v)?.min(100);
                     out.packed &= !Self::BLEND_MASK;
                     out.packed |= blend << Self::BLEND_TRAILING;
                 }
@@ -243,3 +243,36 @@ impl Parse for Kind {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_blend(blend: u64) -> Attributes {
+        Attributes::parse(Value::Map(vec![("blend".into(), blend.into())])).unwrap()
+    }
+
+    #[test]
+    fn blend() {
+        assert_eq!(Attributes::default().blend(), 1.);
+        assert_eq!(with_blend(0).blend(), 1.);
+        assert_eq!(with_blend(30).blend(), 0.7);
+        assert_eq!(with_blend(100).blend(), 0.);
+        // Out of range values are clamped rather than overflowing the field
+        assert_eq!(with_blend(127).blend(), 0.);
+        assert_eq!(with_blend(1000).blend(), 0.);
+    }
+
+    #[test]
+    fn blend_leaves_other_attributes() {
+        let attrs = Attributes::parse(Value::Map(vec![
+            ("bold".into(), true.into()),
+            ("blend".into(), 1000.into()),
+            ("underdashed".into(), true.into()),
+        ]))
+        .unwrap();
+        assert!(attrs.bold());
+        assert!(attrs.underdashed());
+        assert_eq!(attrs.blend(), 0.);
+    }
+}
@@ -5,13 +5,31 @@ macro_rules! msgpack_ext {
     ($x:ident, $doc:meta) => {
         #[derive(Debug, Clone)]
         #[$doc]
-        pub struct $x(#[allow(unused)] Value);
+        pub struct $x(Value);
 
         impl Parse for $x {
             fn parse(value: Value) -> Option<Self> {
                 Some(Self(value))
             }
         }
+
+        impl $x {
+            /// The handle number, as used by the Lua API
+            pub fn number(&self) -> Option<i64> {
+                match &self.0 {
+                    Value::Ext(_, data) => rmpv::decode::read_value(&mut data.as_slice())
+                        .ok()?
+                        .as_i64(),
+                    value => value.as_i64(),
+                }
+            }
+        }
+
+        impl From<$x> for Value {
+            fn from(handle: $x) -> Self {
+                handle.0
+            }
+        }
     };
 }
 
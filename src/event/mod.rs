@@ -16,7 +16,7 @@ mod grid_scroll;
 pub mod hl_attr_define;
 mod hl_group_set;
 mod message_content;
-mod messagepack_ext_types;
+pub mod messagepack_ext_types;
 mod mode_change;
 pub mod mode_info_set;
 pub mod msg_history_show;
@@ -57,7 +57,7 @@ pub use self::{
     grid_scroll::GridScroll,
     hl_attr_define::HlAttrDefine,
     hl_group_set::HlGroupSet,
-    message_content::Content,
+    message_content::{Content, ContentChunk},
     mode_change::ModeChange,
     mode_info_set::ModeInfoSet,
     msg_history_show::MsgHistoryShow,
@@ -2,8 +2,6 @@ use super::messagepack_ext_types::Window;
 use crate::util::{Parse, Values};
 use rmpv::Value;
 
-// TODO: Figure out how to use this event
-
 /// Indicates the margins of a window grid which are _not_ part of the viewport
 /// as indicated by the `win_viewport` event. This happens in the presence of
 /// `winbar` and floating window borders.
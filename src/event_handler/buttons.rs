@@ -1,26 +1,58 @@
-use crate::neovim::button::Button;
+use crate::{neovim::button::Button, ui::grid, util::vec2::CellVec};
+use std::time::{Duration, Instant};
 
 #[derive(PartialEq, Eq, Clone, Copy, Default, PartialOrd, Ord, Debug)]
 pub struct Buttons(u8);
 
 #[rustfmt::skip]
 impl Buttons {
-    const LEFT:   u8 = 0b001;
-    const RIGHT:  u8 = 0b010;
-    const MIDDLE: u8 = 0b100;
+    const LEFT:   u8 = 0b00001;
+    const RIGHT:  u8 = 0b00010;
+    const MIDDLE: u8 = 0b00100;
+    const X1:     u8 = 0b01000;
+    const X2:     u8 = 0b10000;
 }
 
 impl Buttons {
+    const fn with_bit(self, bit: u8, value: bool) -> Self {
+        if value {
+            Self(self.0 | bit)
+        } else {
+            Self(self.0 & !bit)
+        }
+    }
+
     pub const fn with_left(self, value: bool) -> Self {
-        Self(self.0 | (Self::LEFT * value as u8))
+        self.with_bit(Self::LEFT, value)
     }
 
     pub const fn with_right(self, value: bool) -> Self {
-        Self(self.0 | (Self::RIGHT * value as u8))
+        self.with_bit(Self::RIGHT, value)
     }
 
     pub const fn with_middle(self, value: bool) -> Self {
-        Self(self.0 | (Self::MIDDLE * value as u8))
+        self.with_bit(Self::MIDDLE, value)
+    }
+
+    pub const fn with_x1(self, value: bool) -> Self {
+        self.with_bit(Self::X1, value)
+    }
+
+    pub const fn with_x2(self, value: bool) -> Self {
+        self.with_bit(Self::X2, value)
+    }
+
+    /// Set whether the given button is pressed. Buttons that cannot be held,
+    /// such as the wheel, are ignored.
+    pub const fn with(self, button: Button, value: bool) -> Self {
+        match button {
+            Button::Left => self.with_left(value),
+            Button::Right => self.with_right(value),
+            Button::Middle => self.with_middle(value),
+            Button::X1 => self.with_x1(value),
+            Button::X2 => self.with_x2(value),
+            Button::Wheel | Button::Move => self,
+        }
     }
 
     pub const fn left(self) -> bool {
@@ -35,6 +67,14 @@ impl Buttons {
         self.0 & Self::MIDDLE > 0
     }
 
+    pub const fn x1(self) -> bool {
+        self.0 & Self::X1 > 0
+    }
+
+    pub const fn x2(self) -> bool {
+        self.0 & Self::X2 > 0
+    }
+
     pub fn first(&self) -> Option<Button> {
         if self.left() {
             Some(Button::Left)
@@ -42,8 +82,131 @@ impl Buttons {
             Some(Button::Right)
         } else if self.middle() {
             Some(Button::Middle)
+        } else if self.x1() {
+            Some(Button::X1)
+        } else if self.x2() {
+            Some(Button::X2)
         } else {
             None
         }
     }
 }
+
+/// Counts repeated presses of the same button in the same cell to detect
+/// double, triple, and quadruple clicks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Clicks {
+    last: Option<Click>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Click {
+    button: Button,
+    grid: grid::Id,
+    position: CellVec<u32>,
+    time: Instant,
+    count: u8,
+}
+
+impl Clicks {
+    /// The most clicks Neovim distinguishes between. Clicking again starts
+    /// over from a single click.
+    pub const MAX: u8 = 4;
+
+    /// Register a button press and return the number of consecutive clicks
+    /// it makes, starting at 1
+    pub fn press(
+        &mut self,
+        button: Button,
+        grid: grid::Id,
+        position: CellVec<u32>,
+        time: Instant,
+        interval: Duration,
+    ) -> u8 {
+        let count = match self.last {
+            Some(last)
+                if last.button == button
+                    && last.grid == grid
+                    && last.position == position
+                    && last.count < Self::MAX
+                    && time.saturating_duration_since(last.time) <= interval =>
+            {
+                last.count + 1
+            }
+            _ => 1,
+        };
+        self.last = Some(Click {
+            button,
+            grid,
+            position,
+            time,
+            count,
+        });
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INTERVAL: Duration = Duration::from_millis(500);
+
+    #[test]
+    fn buttons_set_and_clear() {
+        let buttons = Buttons::default().with_left(true).with_right(true);
+        assert!(buttons.left() && buttons.right());
+        let buttons = buttons.with_left(false);
+        assert!(!buttons.left() && buttons.right());
+        assert_eq!(buttons.first(), Some(Button::Right));
+        let buttons = buttons.with_right(false);
+        assert_eq!(buttons, Buttons::default());
+        assert_eq!(buttons.first(), None);
+    }
+
+    #[test]
+    fn extra_buttons() {
+        let buttons = Buttons::default().with(Button::X2, true);
+        assert_eq!(buttons.first(), Some(Button::X2));
+        let buttons = buttons.with(Button::X1, true).with(Button::X2, false);
+        assert_eq!(buttons.first(), Some(Button::X1));
+        assert_eq!(buttons.with(Button::Wheel, true), buttons);
+    }
+
+    #[test]
+    fn repeated_clicks() {
+        let mut clicks = Clicks::default();
+        let start = Instant::now();
+        let position = CellVec::new(3, 4);
+        let counts: Vec<_> = (0..6)
+            .map(|i| {
+                let time = start + Duration::from_millis(100) * i;
+                clicks.press(Button::Left, 1, position, time, INTERVAL)
+            })
+            .collect();
+        assert_eq!(counts, [1, 2, 3, 4, 1, 2]);
+    }
+
+    #[test]
+    fn click_interval() {
+        let mut clicks = Clicks::default();
+        let start = Instant::now();
+        let position = CellVec::new(0, 0);
+        assert_eq!(clicks.press(Button::Left, 1, position, start, INTERVAL), 1);
+        let late = start + INTERVAL + Duration::from_millis(1);
+        assert_eq!(clicks.press(Button::Left, 1, position, late, INTERVAL), 1);
+    }
+
+    #[test]
+    fn clicks_reset_on_change() {
+        let mut clicks = Clicks::default();
+        let time = Instant::now();
+        let position = CellVec::new(0, 0);
+        assert_eq!(clicks.press(Button::Left, 1, position, time, INTERVAL), 1);
+        assert_eq!(clicks.press(Button::Right, 1, position, time, INTERVAL), 1);
+        assert_eq!(clicks.press(Button::Right, 2, position, time, INTERVAL), 1);
+        let moved = CellVec::new(1, 0);
+        assert_eq!(clicks.press(Button::Right, 2, moved, time, INTERVAL), 1);
+        assert_eq!(clicks.press(Button::Right, 2, moved, time, INTERVAL), 2);
+    }
+}
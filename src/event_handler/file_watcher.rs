@@ -0,0 +1,58 @@
+//! Watches a file for changes from another thread so that the event loop only
+//! wakes up when something changed
+
+use crate::UserEvent;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, SystemTime},
+};
+use winit::event_loop::EventLoopProxy;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Sends the given event whenever the file's modification time changes. The
+/// watching stops when this is dropped.
+pub struct FileWatcher {
+    stopped: Arc<AtomicBool>,
+}
+
+impl FileWatcher {
+    pub fn new(path: PathBuf, proxy: EventLoopProxy<UserEvent>, event: fn() -> UserEvent) -> Self {
+        let stopped = Arc::new(AtomicBool::new(false));
+        let stopped_thread = stopped.clone();
+        thread::spawn(move || {
+            let mut last_modified = modified(&path);
+            loop {
+                thread::sleep(POLL_INTERVAL);
+                if stopped_thread.load(Ordering::Relaxed) {
+                    break;
+                }
+                let modified = modified(&path);
+                if modified != last_modified {
+                    last_modified = modified;
+                    if proxy.send_event(event()).is_err() {
+                        // The event loop has closed
+                        break;
+                    }
+                }
+            }
+        });
+        Self { stopped }
+    }
+}
+
+impl Drop for FileWatcher {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Relaxed);
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
@@ -0,0 +1,213 @@
+//! Shortcuts handled by Neophyte before keys are sent to Neovim
+
+use crate::util::{parse_map, Parse};
+use rmpv::Value;
+use std::collections::HashMap;
+
+/// Something Neophyte does itself in response to a shortcut
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GuiAction {
+    ToggleFullscreen,
+    ZoomIn,
+    ZoomOut,
+    ZoomReset,
+    Copy,
+    Paste,
+    NewWindow,
+}
+
+impl GuiAction {
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "toggle_fullscreen" => Self::ToggleFullscreen,
+            "zoom_in" => Self::ZoomIn,
+            "zoom_out" => Self::ZoomOut,
+            "zoom_reset" => Self::ZoomReset,
+            "copy" => Self::Copy,
+            "paste" => Self::Paste,
+            "new_window" => Self::NewWindow,
+            _ => return None,
+        })
+    }
+}
+
+/// What to do when a key is pressed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Binding {
+    Action(GuiAction),
+    /// Send the given keys to Neovim instead
+    Input(String),
+    /// Call the Lua callback registered for the keys, as they were given to
+    /// the Lua map function
+    Lua(String),
+}
+
+impl Parse for Binding {
+    fn parse(value: Value) -> Option<Self> {
+        let (k, v) = parse_map(value)?.into_iter().next()?;
+        match k.as_str()? {
+            "action" => GuiAction::from_name(v.as_str()?).map(Self::Action),
+            "input" => Some(Self::Input(v.as_str()?.to_string())),
+            "lua" => Some(Self::Lua(v.as_str()?.to_string())),
+            _ => None,
+        }
+    }
+}
+
+/// Keybindings from the user, falling back to the default ones
+#[derive(Debug, Clone)]
+pub struct Keybindings {
+    user: HashMap<String, Option<Binding>>,
+    defaults: HashMap<String, Binding>,
+    pub use_defaults: bool,
+}
+
+impl Keybindings {
+    pub fn new() -> Self {
+        let defaults = DEFAULTS
+            .iter()
+            .map(|(keys, action)| (normalize(keys), Binding::Action(*action)))
+            .collect();
+        Self {
+            user: HashMap::new(),
+            defaults,
+            use_defaults: true,
+        }
+    }
+
+    /// Bind the keys, or unbind them if the binding is None. Unbinding also
+    /// disables the default binding for the keys, if any.
+    pub fn set(&mut self, keys: &str, binding: Option<Binding>) {
+        self.user.insert(normalize(keys), binding);
+    }
+
+    /// Get the binding for keys encoded by the keyboard module
+    pub fn get(&self, keys: &str) -> Option<&Binding> {
+        let keys = normalize(keys);
+        match self.user.get(&keys) {
+            Some(binding) => binding.as_ref(),
+            None if self.use_defaults => self.defaults.get(&keys),
+            None => None,
+        }
+    }
+}
+
+impl Default for Keybindings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(target_os = "macos")]
+const DEFAULTS: &[(&str, GuiAction)] = &[
+    ("<D-C-f>", GuiAction::ToggleFullscreen),
+    ("<D-=>", GuiAction::ZoomIn),
+    ("<D-+>", GuiAction::ZoomIn),
+    ("<D-->", GuiAction::ZoomOut),
+    ("<D-0>", GuiAction::ZoomReset),
+    ("<D-c>", GuiAction::Copy),
+    ("<D-v>", GuiAction::Paste),
+    ("<D-n>", GuiAction::NewWindow),
+];
+
+/// Only keys that Neovim mappings rarely use, since the defaults take them
+/// before Neovim sees them. Zooming has no default for that reason.
+#[cfg(not(target_os = "macos"))]
+const DEFAULTS: &[(&str, GuiAction)] = &[
+    ("<F11>", GuiAction::ToggleFullscreen),
+    ("<C-S-c>", GuiAction::Copy),
+    ("<C-S-v>", GuiAction::Paste),
+    ("<C-S-n>", GuiAction::NewWindow),
+];
+
+/// Put key notation in the canonical form produced by the keyboard module so
+/// that equivalent notations match. Modifiers are sorted and keycode names
+/// are case-insensitive. Shift with a letter is written as the uppercase
+/// letter since that is how the keyboard module reports it.
+fn normalize(keys: &str) -> String {
+    let Some(inner) = keys
+        .strip_prefix('<')
+        .and_then(|keys| keys.strip_suffix('>'))
+    else {
+        return keys.to_string();
+    };
+
+    let (mut ctrl, mut shift, mut alt, mut logo) = (false, false, false, false);
+    let mut rest = inner;
+    // The key itself may be a dash, as in <C-->, so the last character is
+    // never treated as a separator
+    while let Some((modifier, tail)) = rest.split_once('-') {
+        if tail.is_empty() {
+            break;
+        }
+        match modifier.to_ascii_uppercase().as_str() {
+            "C" => ctrl = true,
+            "S" => shift = true,
+            "A" | "M" => alt = true,
+            "D" => logo = true,
+            _ => return keys.to_string(),
+        }
+        rest = tail;
+    }
+
+    let mut key = if rest.chars().count() == 1 {
+        rest.to_string()
+    } else {
+        rest.to_lowercase()
+    };
+    if shift && key.chars().count() == 1 && key.chars().all(char::is_alphabetic) {
+        shift = false;
+        key = key.to_uppercase();
+    }
+
+    let mut out = String::from("<");
+    for (enabled, prefix) in [(ctrl, "C-"), (shift, "S-"), (alt, "A-"), (logo, "D-")] {
+        if enabled {
+            out.push_str(prefix);
+        }
+    }
+    if out.len() == 1 && key.chars().count() == 1 {
+        return key;
+    }
+    out.push_str(&key);
+    out.push('>');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_keys() {
+        let cases = [
+            ("a", "a"),
+            ("jk", "jk"),
+            ("<C-S-c>", "<C-C>"),
+            ("<S-C-c>", "<C-C>"),
+            ("<C-C>", "<C-C>"),
+            ("<C-->", "<C-->"),
+            ("<C-+>", "<C-+>"),
+            ("<c-F5>", "<C-f5>"),
+            ("<C-f5>", "<C-f5>"),
+            ("<M-x>", "<A-x>"),
+            ("<A-C-D-x>", "<C-A-D-x>"),
+            ("<S-Tab>", "<S-tab>"),
+            ("<Esc>", "<esc>"),
+            ("<lt>", "<lt>"),
+            ("<x>", "x"),
+            ("<Q-x>", "<Q-x>"),
+        ];
+        for (keys, expected) in cases {
+            assert_eq!(normalize(keys), expected, "{keys}");
+        }
+    }
+
+    #[test]
+    fn defaults_leave_common_mappings_to_neovim() {
+        let keybindings = Keybindings::new();
+        for keys in ["<C-=>", "<C-+>", "<C-->", "<C-0>", "<C-c>", "<C-v>"] {
+            assert_eq!(keybindings.get(keys), None, "{keys}");
+        }
+    }
+}
@@ -0,0 +1,643 @@
+//! Translates winit key events into Neovim key notation. See
+//! https://neovim.io/doc/user/intro.html#keycodes
+
+use winit::{
+    event::KeyEvent,
+    keyboard::{Key, KeyLocation, ModifiersState, NamedKey, SmolStr},
+    platform::modifier_supplement::KeyEventExtModifierSupplement,
+};
+
+/// The parts of a key event that translation depends on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyPress {
+    pub key: Key,
+    pub location: KeyLocation,
+    /// The text committed by the key press
+    pub text: Option<SmolStr>,
+    /// The key as it would be without any modifiers
+    pub unmodified: Key,
+}
+
+impl From<&KeyEvent> for KeyPress {
+    fn from(event: &KeyEvent) -> Self {
+        Self {
+            key: event.logical_key.clone(),
+            location: event.location,
+            text: event.text.clone(),
+            unmodified: event.key_without_modifiers(),
+        }
+    }
+}
+
+/// The result of translating a key press
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Encoded {
+    /// Key notation to send to Neovim with nvim_input
+    Keys(String),
+    /// A key without a Neovim keycode, such as a media key, named in angle
+    /// brackets. These are only forwarded to raw input handlers.
+    Other(String),
+}
+
+/// Keyboard state that spans multiple key presses
+#[derive(Debug, Clone, Default)]
+pub struct Keyboard {
+    /// The accent of a dead key waiting to be combined with the next key
+    dead: Option<char>,
+    /// Whether AltGr is held, on platforms that report it as a key
+    alt_graph: bool,
+}
+
+impl Keyboard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Translate a key press into Neovim key notation. Dead keys are held
+    /// until the next key press, which they are combined with if possible.
+    pub fn input(&mut self, press: &KeyPress, modifiers: ModifiersState) -> Option<Encoded> {
+        if press.key == Key::Named(NamedKey::AltGraph) {
+            self.alt_graph = true;
+        }
+
+        if let Key::Dead(accent) = press.key {
+            return match (self.dead.take(), accent) {
+                // Pressing a dead key twice types the accent
+                (Some(pending), Some(accent)) if pending == accent => {
+                    Some(Encoded::Keys(character(&accent.to_string(), modifiers)))
+                }
+                (Some(pending), accent) => {
+                    self.dead = accent;
+                    Some(Encoded::Keys(character(&pending.to_string(), modifiers)))
+                }
+                (None, accent) => {
+                    self.dead = accent;
+                    None
+                }
+            };
+        }
+
+        let text = committed_text(press, modifiers, self.alt_graph);
+        let Some(dead) = self.dead else {
+            return match text {
+                Some(text) => Some(Encoded::Keys(character(text, ModifiersState::empty()))),
+                None => encode(&press.key, press.location, modifiers),
+            };
+        };
+
+        match &press.key {
+            Key::Named(named) if is_modifier(*named) => None,
+            Key::Named(NamedKey::Escape) => {
+                self.dead = None;
+                encode(&press.key, press.location, modifiers)
+            }
+            Key::Named(NamedKey::Space) => {
+                self.dead = None;
+                Some(Encoded::Keys(character(&dead.to_string(), modifiers)))
+            }
+            Key::Character(base) => {
+                self.dead = None;
+                // The platform may have composed the character already
+                let composed = match text {
+                    Some(text) if text != base.as_str() => Some(text.to_string()),
+                    _ => compose(dead, base).map(|c| c.to_string()),
+                };
+                match composed {
+                    Some(composed) => Some(Encoded::Keys(character(&composed, modifiers))),
+                    None => {
+                        let mut keys = character(&dead.to_string(), ModifiersState::empty());
+                        keys.push_str(&character(base, modifiers));
+                        Some(Encoded::Keys(keys))
+                    }
+                }
+            }
+            _ => {
+                self.dead = None;
+                let accent = character(&dead.to_string(), ModifiersState::empty());
+                match encode(&press.key, press.location, modifiers) {
+                    Some(Encoded::Keys(keys)) => Some(Encoded::Keys(accent + &keys)),
+                    other => other,
+                }
+            }
+        }
+    }
+
+    /// Track the release of a key
+    pub fn release(&mut self, key: &Key) {
+        if *key == Key::Named(NamedKey::AltGraph) {
+            self.alt_graph = false;
+        }
+    }
+}
+
+/// The text committed by the key press, if it should be sent as is rather
+/// than being encoded from the logical key. This is the case for characters
+/// typed with AltGr, which some platforms report as Ctrl+Alt. Ctrl+Alt
+/// chords that type the key's usual character, such as Ctrl+Option on macOS,
+/// are encoded with their modifiers instead.
+fn committed_text(press: &KeyPress, modifiers: ModifiersState, alt_graph: bool) -> Option<&str> {
+    if press.location == KeyLocation::Numpad {
+        return None;
+    }
+    let text = press.text.as_deref()?;
+    // Space is left to the logical key so that Shift+Space is kept
+    if text.is_empty() || text == " " || text.chars().any(char::is_control) {
+        return None;
+    }
+    let changed = !matches!(
+        &press.unmodified,
+        Key::Character(c) if c.to_lowercase() == text.to_lowercase()
+    );
+    let alt_gr = alt_graph || (modifiers.control_key() && modifiers.alt_key() && changed);
+    let plain = !modifiers.control_key() && !modifiers.alt_key() && !modifiers.super_key();
+    (alt_gr || plain).then_some(text)
+}
+
+/// Combine the accent of a dead key with the following character
+fn compose(accent: char, base: &str) -> Option<char> {
+    let mut chars = base.chars();
+    let (base, None) = (chars.next()?, chars.next()) else {
+        return None;
+    };
+    let (from, to) = match accent {
+        '`' => ("aeiouAEIOU", "àèìòùÀÈÌÒÙ"),
+        '´' | '\'' => ("aeiouyAEIOUYcnszCNSZ", "áéíóúýÁÉÍÓÚÝćńśźĆŃŚŹ"),
+        '^' | 'ˆ' => ("aeiouAEIOU", "âêîôûÂÊÎÔÛ"),
+        '~' | '˜' => ("anoANO", "ãñõÃÑÕ"),
+        '¨' | '"' => ("aeiouyAEIOUY", "äëïöüÿÄËÏÖÜŸ"),
+        '°' | '˚' => ("auAU", "åůÅŮ"),
+        '¸' => ("csCS", "çşÇŞ"),
+        'ˇ' => ("cdenrstzCDENRSTZ", "čďěňřšťžČĎĚŇŘŠŤŽ"),
+        _ => return None,
+    };
+    from.chars()
+        .position(|c| c == base)
+        .and_then(|i| to.chars().nth(i))
+}
+
+/// Translate the key into Neovim key notation, accounting for the location of
+/// the key to tell keypad keys apart
+fn encode(key: &Key, location: KeyLocation, modifiers: ModifiersState) -> Option<Encoded> {
+    let keypad = location == KeyLocation::Numpad;
+    match key {
+        Key::Named(named) => {
+            if let Some(keycode) = keypad.then(|| keypad_named(*named)).flatten() {
+                Some(Encoded::Keys(with_modifiers(keycode, modifiers, false)))
+            } else if let Some(keycode) = named_keycode(*named) {
+                Some(Encoded::Keys(with_modifiers(keycode, modifiers, false)))
+            } else if is_modifier(*named) {
+                None
+            } else {
+                let name = format!("{named:?}");
+                Some(Encoded::Other(with_modifiers(&name, modifiers, false)))
+            }
+        }
+
+        Key::Character(c) => {
+            if let Some(keycode) = keypad.then(|| keypad_character(c)).flatten() {
+                Some(Encoded::Keys(with_modifiers(keycode, modifiers, false)))
+            } else {
+                Some(Encoded::Keys(character(c, modifiers)))
+            }
+        }
+
+        Key::Unidentified(_) | Key::Dead(_) => None,
+    }
+}
+
+/// Encode text produced by a key. Shift is already reflected in the
+/// character, so it is only included for keys that have a keycode name.
+fn character(c: &str, modifiers: ModifiersState) -> String {
+    let keycode = match c {
+        "<" => Some("lt"),
+        "\\" => Some("Bslash"),
+        "|" => Some("Bar"),
+        " " => Some("Space"),
+        _ => None,
+    };
+    match keycode {
+        // Shift+Space is distinguishable where shifted punctuation is not
+        Some("Space") => with_modifiers("Space", modifiers, false),
+        Some(keycode) => with_modifiers(keycode, modifiers, true),
+        None => with_modifiers(c, modifiers, true),
+    }
+}
+
+/// Wrap the key in angle brackets with the active modifiers as needed
+fn with_modifiers(key: &str, modifiers: ModifiersState, ignore_shift: bool) -> String {
+    let mut prefix = String::new();
+    if modifiers.control_key() {
+        prefix.push_str("C-");
+    }
+    if modifiers.shift_key() && !ignore_shift {
+        prefix.push_str("S-");
+    }
+    if modifiers.alt_key() {
+        prefix.push_str("A-");
+    }
+    if modifiers.super_key() {
+        prefix.push_str("D-");
+    }
+
+    if prefix.is_empty() && key.chars().count() == 1 {
+        key.to_string()
+    } else {
+        format!("<{prefix}{key}>")
+    }
+}
+
+/// Modifier and lock keys, which only take effect in combination with other
+/// keys
+fn is_modifier(key: NamedKey) -> bool {
+    use NamedKey::*;
+    matches!(
+        key,
+        Alt | AltGraph
+            | CapsLock
+            | Control
+            | Fn
+            | FnLock
+            | NumLock
+            | ScrollLock
+            | Shift
+            | Symbol
+            | SymbolLock
+            | Meta
+            | Hyper
+            | Super
+    )
+}
+
+/// Keycodes for keys on the keypad that are not characters, such as with
+/// Num Lock off
+fn keypad_named(key: NamedKey) -> Option<&'static str> {
+    use NamedKey::*;
+    Some(match key {
+        Enter => "kEnter",
+        ArrowUp => "kUp",
+        ArrowDown => "kDown",
+        ArrowLeft => "kLeft",
+        ArrowRight => "kRight",
+        Home => "kHome",
+        End => "kEnd",
+        PageUp => "kPageUp",
+        PageDown => "kPageDown",
+        Insert => "kInsert",
+        Delete => "kDel",
+        Clear => "kOrigin",
+        _ => return None,
+    })
+}
+
+/// Keycodes for characters typed on the keypad
+fn keypad_character(c: &str) -> Option<&'static str> {
+    Some(match c {
+        "0" => "k0",
+        "1" => "k1",
+        "2" => "k2",
+        "3" => "k3",
+        "4" => "k4",
+        "5" => "k5",
+        "6" => "k6",
+        "7" => "k7",
+        "8" => "k8",
+        "9" => "k9",
+        "+" => "kPlus",
+        "-" => "kMinus",
+        "*" => "kMultiply",
+        "/" => "kDivide",
+        "." => "kPoint",
+        "," => "kComma",
+        "=" => "kEqual",
+        _ => return None,
+    })
+}
+
+fn named_keycode(key: NamedKey) -> Option<&'static str> {
+    use NamedKey::*;
+    Some(match key {
+        Enter => "Enter",
+        Tab => "Tab",
+        Space => "Space",
+        ArrowDown => "Down",
+        ArrowLeft => "Left",
+        ArrowRight => "Right",
+        ArrowUp => "Up",
+        End => "End",
+        Home => "Home",
+        PageDown => "PageDown",
+        PageUp => "PageUp",
+        Backspace => "BS",
+        Delete => "Del",
+        Escape => "Esc",
+        Insert => "Insert",
+        Help => "Help",
+        Undo => "Undo",
+        ContextMenu => "Menu",
+        F1 => "F1",
+        F2 => "F2",
+        F3 => "F3",
+        F4 => "F4",
+        F5 => "F5",
+        F6 => "F6",
+        F7 => "F7",
+        F8 => "F8",
+        F9 => "F9",
+        F10 => "F10",
+        F11 => "F11",
+        F12 => "F12",
+        F13 => "F13",
+        F14 => "F14",
+        F15 => "F15",
+        F16 => "F16",
+        F17 => "F17",
+        F18 => "F18",
+        F19 => "F19",
+        F20 => "F20",
+        F21 => "F21",
+        F22 => "F22",
+        F23 => "F23",
+        F24 => "F24",
+        F25 => "F25",
+        F26 => "F26",
+        F27 => "F27",
+        F28 => "F28",
+        F29 => "F29",
+        F30 => "F30",
+        F31 => "F31",
+        F32 => "F32",
+        F33 => "F33",
+        F34 => "F34",
+        F35 => "F35",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NONE: ModifiersState = ModifiersState::empty();
+    const SHIFT: ModifiersState = ModifiersState::SHIFT;
+    const CTRL: ModifiersState = ModifiersState::CONTROL;
+    const ALT: ModifiersState = ModifiersState::ALT;
+    const SUPER: ModifiersState = ModifiersState::SUPER;
+
+    fn ch(c: &str) -> Key {
+        Key::Character(c.into())
+    }
+
+    fn keys(keys: &str) -> Option<Encoded> {
+        Some(Encoded::Keys(keys.to_string()))
+    }
+
+    /// A name, key presses, and what each press is expected to encode to
+    type InputCase<'a> = (
+        &'a str,
+        &'a [(KeyPress, ModifiersState)],
+        &'a [Option<Encoded>],
+    );
+
+    /// A key press that types the given text, or nothing if it is empty
+    fn press(key: Key, text: &str) -> KeyPress {
+        KeyPress {
+            unmodified: key.clone(),
+            key,
+            location: KeyLocation::Standard,
+            text: (!text.is_empty()).then(|| text.into()),
+        }
+    }
+
+    #[test]
+    fn encodes_keys() {
+        use KeyLocation::{Numpad, Standard};
+        use NamedKey::*;
+        let cases = [
+            (ch("0"), Numpad, NONE, keys("<k0>")),
+            (ch("9"), Numpad, NONE, keys("<k9>")),
+            (ch("+"), Numpad, NONE, keys("<kPlus>")),
+            (ch("/"), Numpad, CTRL, keys("<C-kDivide>")),
+            (ch("."), Numpad, NONE, keys("<kPoint>")),
+            (Key::Named(Enter), Numpad, NONE, keys("<kEnter>")),
+            (Key::Named(ArrowUp), Numpad, SHIFT, keys("<S-kUp>")),
+            (Key::Named(Clear), Numpad, NONE, keys("<kOrigin>")),
+            (ch("5"), Standard, NONE, keys("5")),
+            (Key::Named(Enter), Standard, NONE, keys("<Enter>")),
+            (Key::Named(Tab), Standard, SHIFT, keys("<S-Tab>")),
+            (Key::Named(Space), Standard, NONE, keys("<Space>")),
+            (Key::Named(Space), Standard, CTRL, keys("<C-Space>")),
+            (Key::Named(Space), Standard, SHIFT, keys("<S-Space>")),
+            (Key::Named(F1), Standard, NONE, keys("<F1>")),
+            (Key::Named(F5), Standard, CTRL | SHIFT, keys("<C-S-F5>")),
+            (Key::Named(F12), Standard, ALT | SUPER, keys("<A-D-F12>")),
+            (ch("<"), Standard, NONE, keys("<lt>")),
+            (ch("\\"), Standard, CTRL, keys("<C-Bslash>")),
+            (ch("|"), Standard, SHIFT, keys("<Bar>")),
+            (ch("A"), Standard, SHIFT, keys("A")),
+            (ch("a"), Standard, CTRL, keys("<C-a>")),
+            (Key::Named(Shift), Standard, SHIFT, None),
+            (Key::Named(AltGraph), Standard, NONE, None),
+            (Key::Dead(Some('`')), Standard, NONE, None),
+            (
+                Key::Named(MediaPlayPause),
+                Standard,
+                NONE,
+                Some(Encoded::Other("<MediaPlayPause>".to_string())),
+            ),
+            (
+                Key::Named(AudioVolumeUp),
+                Standard,
+                CTRL,
+                Some(Encoded::Other("<C-AudioVolumeUp>".to_string())),
+            ),
+        ];
+        for (key, location, modifiers, expected) in cases {
+            assert_eq!(
+                encode(&key, location, modifiers),
+                expected,
+                "{key:?} {location:?} {modifiers:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn encodes_characters() {
+        let cases = [
+            ("a", NONE, "a"),
+            ("é", NONE, "é"),
+            ("x", ALT, "<A-x>"),
+            ("X", SHIFT | CTRL, "<C-X>"),
+            ("<", NONE, "<lt>"),
+            ("<", CTRL, "<C-lt>"),
+            ("\\", NONE, "<Bslash>"),
+            ("|", SHIFT, "<Bar>"),
+            (" ", NONE, "<Space>"),
+            (" ", SHIFT, "<S-Space>"),
+            (" ", CTRL, "<C-Space>"),
+        ];
+        for (c, modifiers, expected) in cases {
+            assert_eq!(character(c, modifiers), expected, "{c:?} {modifiers:?}");
+        }
+    }
+
+    #[test]
+    fn adds_modifiers() {
+        let cases = [
+            ("a", NONE, false, "a"),
+            ("a", SHIFT, true, "a"),
+            ("a", SHIFT, false, "<S-a>"),
+            ("F1", NONE, false, "<F1>"),
+            ("Tab", SHIFT, false, "<S-Tab>"),
+            ("x", CTRL | SHIFT | ALT | SUPER, false, "<C-S-A-D-x>"),
+            ("x", CTRL | SHIFT | ALT | SUPER, true, "<C-A-D-x>"),
+        ];
+        for (key, modifiers, ignore_shift, expected) in cases {
+            assert_eq!(
+                with_modifiers(key, modifiers, ignore_shift),
+                expected,
+                "{key:?} {modifiers:?} {ignore_shift}"
+            );
+        }
+    }
+
+    #[test]
+    fn composes_accents() {
+        let cases = [
+            ('`', "a", Some('à')),
+            ('´', "e", Some('é')),
+            ('\'', "C", Some('Ć')),
+            ('^', "O", Some('Ô')),
+            ('~', "n", Some('ñ')),
+            ('¨', "u", Some('ü')),
+            ('°', "A", Some('Å')),
+            ('¸', "c", Some('ç')),
+            ('ˇ', "z", Some('ž')),
+            ('~', "x", None),
+            ('`', "ab", None),
+            ('`', "", None),
+            ('x', "a", None),
+        ];
+        for (accent, base, expected) in cases {
+            assert_eq!(compose(accent, base), expected, "{accent:?} {base:?}");
+        }
+    }
+
+    #[test]
+    fn keyboard_input() {
+        let dead = |accent| press(Key::Dead(Some(accent)), "");
+        let named = |key| press(Key::Named(key), "");
+        let cases: &[InputCase] = &[
+            (
+                "plain text",
+                &[(press(ch("a"), "a"), NONE), (press(ch("A"), "A"), SHIFT)],
+                &[keys("a"), keys("A")],
+            ),
+            (
+                "control character",
+                &[(press(ch("a"), "\u{1}"), CTRL)],
+                &[keys("<C-a>")],
+            ),
+            (
+                "keypad text",
+                &[(
+                    KeyPress {
+                        location: KeyLocation::Numpad,
+                        ..press(ch("1"), "1")
+                    },
+                    NONE,
+                )],
+                &[keys("<k1>")],
+            ),
+            (
+                "media key",
+                &[(named(NamedKey::MediaPlayPause), NONE)],
+                &[Some(Encoded::Other("<MediaPlayPause>".to_string()))],
+            ),
+            (
+                "compose",
+                &[(dead('`'), NONE), (press(ch("a"), "a"), NONE)],
+                &[None, keys("à")],
+            ),
+            (
+                "composed by the platform",
+                &[(dead('´'), NONE), (press(ch("a"), "á"), NONE)],
+                &[None, keys("á")],
+            ),
+            (
+                "double press",
+                &[(dead('`'), NONE), (dead('`'), NONE)],
+                &[None, keys("`")],
+            ),
+            (
+                "different dead key",
+                &[
+                    (dead('`'), NONE),
+                    (dead('^'), NONE),
+                    (press(ch("e"), "e"), NONE),
+                ],
+                &[None, keys("`"), keys("ê")],
+            ),
+            (
+                "space",
+                &[(dead('¨'), NONE), (named(NamedKey::Space), NONE)],
+                &[None, keys("¨")],
+            ),
+            (
+                "no composition",
+                &[(dead('`'), NONE), (press(ch("x"), "x"), NONE)],
+                &[None, keys("`x")],
+            ),
+            (
+                "escape",
+                &[(dead('`'), NONE), (named(NamedKey::Escape), NONE)],
+                &[None, keys("<Esc>")],
+            ),
+            (
+                "named key",
+                &[(dead('`'), NONE), (named(NamedKey::Enter), NONE)],
+                &[None, keys("`<Enter>")],
+            ),
+            (
+                "modifier between",
+                &[
+                    (dead('^'), NONE),
+                    (named(NamedKey::Shift), SHIFT),
+                    (press(ch("A"), "A"), SHIFT),
+                ],
+                &[None, None, keys("Â")],
+            ),
+            (
+                "AltGr as Ctrl+Alt",
+                &[(
+                    KeyPress {
+                        unmodified: ch("q"),
+                        ..press(ch("@"), "@")
+                    },
+                    CTRL | ALT,
+                )],
+                &[keys("@")],
+            ),
+            (
+                "Ctrl+Alt chord",
+                &[(press(ch("a"), "a"), CTRL | ALT)],
+                &[keys("<C-A-a>")],
+            ),
+            (
+                "AltGraph key",
+                &[
+                    (named(NamedKey::AltGraph), NONE),
+                    (press(ch("e"), "€"), NONE),
+                ],
+                &[None, keys("€")],
+            ),
+        ];
+        for (name, presses, expected) in cases {
+            let mut keyboard = Keyboard::new();
+            let encoded: Vec<_> = presses
+                .iter()
+                .map(|(press, modifiers)| keyboard.input(press, *modifiers))
+                .collect();
+            assert_eq!(encoded, *expected, "{name}");
+        }
+    }
+}
@@ -0,0 +1,173 @@
+//! Finds and opens hyperlinks in grid text
+
+use crate::ui::{grid, GridUnderCursor, Ui};
+use std::{io, ops::Range, process::Command, thread};
+
+/// Schemes that are recognized in text and may be given to the system opener.
+/// Other links, such as file:// ones from the url highlight attribute, are
+/// only opened by the Lua link handler since the opener may run them.
+const SCHEMES: [&str; 3] = ["https://", "http://", "mailto:"];
+
+/// A hyperlink in a grid row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Link {
+    pub grid: grid::Id,
+    pub row: u32,
+    /// The cell columns the link spans
+    pub columns: Range<u32>,
+    pub url: String,
+}
+
+/// Find the hyperlink at the given cell. Links come from the url highlight
+/// attribute, or failing that, from URLs in the text.
+pub fn link_at(ui: &Ui, cell: GridUnderCursor) -> Option<Link> {
+    let contents = ui.grid(cell.grid)?.contents();
+    let row = cell.position.0.y;
+    let col = cell.position.0.x as usize;
+    let cells: Vec<_> = contents
+        .rows()
+        .nth(row as usize)?
+        .map(|cell| {
+            let text: String = cell.text.filter(|&c| c != '\0').collect();
+            (text, cell.highlight)
+        })
+        .collect();
+    let highlight = cells.get(col)?.1;
+
+    if let Some(url) = ui.highlight_urls.get(&highlight) {
+        let same_highlight = |i: &usize| cells[*i].1 == highlight;
+        let start = (0..col)
+            .rev()
+            .take_while(same_highlight)
+            .last()
+            .unwrap_or(col);
+        let end = (col..cells.len()).take_while(same_highlight).last()? + 1;
+        return Some(Link {
+            grid: cell.grid,
+            row,
+            columns: start as u32..end as u32,
+            url: url.clone(),
+        });
+    }
+
+    // Byte offsets into the row text where each cell starts
+    let mut offsets = Vec::with_capacity(cells.len());
+    let mut text = String::new();
+    for (cell_text, _) in cells.iter() {
+        offsets.push(text.len());
+        text.push_str(cell_text);
+    }
+
+    let link = find_urls(&text)
+        .map(|range| {
+            let start = offsets.partition_point(|&offset| offset <= range.start) - 1;
+            let end = offsets.partition_point(|&offset| offset < range.end);
+            (start..end, range)
+        })
+        .find(|(columns, _)| columns.contains(&col))
+        .map(|(columns, range)| Link {
+            grid: cell.grid,
+            row,
+            columns: columns.start as u32..columns.end as u32,
+            url: text[range].to_string(),
+        });
+    link
+}
+
+/// Find the byte ranges of URLs in the text
+fn find_urls(text: &str) -> impl Iterator<Item = Range<usize>> + '_ {
+    let mut i = 0;
+    std::iter::from_fn(move || loop {
+        let rest = text.get(i..)?;
+        let (offset, scheme) = SCHEMES
+            .iter()
+            .filter_map(|scheme| Some((rest.find(scheme)?, scheme)))
+            .min()?;
+        let start = i + offset;
+        let len = text[start..]
+            .find(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '"' | '\'' | '`'))
+            .unwrap_or(text.len() - start);
+        let url = trim_url(&text[start..start + len]);
+        let end = start + url.len();
+        i = start + scheme.len();
+        // Schemes must begin a word
+        let begins_word = !text[..start]
+            .chars()
+            .next_back()
+            .is_some_and(char::is_alphanumeric);
+        if begins_word && url.len() > scheme.len() {
+            i = end;
+            return Some(start..end);
+        }
+    })
+}
+
+/// Remove trailing punctuation that is more likely part of the surrounding
+/// text than the URL, keeping closing brackets that have a match in the URL
+fn trim_url(mut url: &str) -> &str {
+    while let Some(last) = url.chars().next_back() {
+        let unbalanced = |open, close| url.matches(open).count() < url.matches(close).count();
+        let trim = match last {
+            '.' | ',' | ';' | ':' | '!' | '?' => true,
+            ')' => unbalanced('(', ')'),
+            ']' => unbalanced('[', ']'),
+            _ => false,
+        };
+        if !trim {
+            break;
+        }
+        url = &url[..url.len() - last.len_utf8()];
+    }
+    url
+}
+
+#[cfg(target_os = "macos")]
+const OPENER: &str = "open";
+/// Explorer takes the URL as a plain argument, unlike cmd, which would
+/// interpret characters in it such as % and &
+#[cfg(target_os = "windows")]
+const OPENER: &str = "explorer";
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const OPENER: &str = "xdg-open";
+
+/// Open the URL with the default application for it
+pub fn open(url: &str) -> io::Result<()> {
+    if !SCHEMES.iter().any(|scheme| url.starts_with(scheme)) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "only http, https, and mailto links can be opened without a link handler",
+        ));
+    }
+
+    let mut child = Command::new(OPENER).arg(url).spawn()?;
+    // Reap the process once the opener exits
+    thread::spawn(move || child.wait());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_urls() {
+        let text = "see https://example.com/a_(b). or mailto:me@example.com, not file:///etc";
+        let urls: Vec<_> = find_urls(text).map(|range| &text[range]).collect();
+        assert_eq!(urls, ["https://example.com/a_(b)", "mailto:me@example.com"]);
+    }
+
+    #[test]
+    fn only_opens_web_and_mail_links() {
+        for url in [
+            "file:///bin/sh",
+            "javascript:alert(1)",
+            "C:\\Windows\\notepad.exe",
+        ] {
+            assert_eq!(
+                open(url).unwrap_err().kind(),
+                io::ErrorKind::InvalidInput,
+                "{url}"
+            );
+        }
+    }
+}
@@ -1,26 +1,44 @@
 mod buttons;
+mod file_watcher;
+mod keybindings;
+mod keyboard;
+mod links;
+mod pointer;
 pub mod settings;
 
-use self::{buttons::Buttons, settings::Settings};
+use self::{
+    buttons::{Buttons, Clicks},
+    file_watcher::FileWatcher,
+    keybindings::{Binding, GuiAction, Keybindings},
+    keyboard::{Encoded, KeyPress, Keyboard},
+    links::Link,
+    pointer::{PointerSettings, Region},
+    settings::Settings,
+};
 use crate::{
-    event::{self, rgb::Rgb},
-    neovim::{action::Action, button::Button, Neovim},
-    rendering::{state::RenderState, Motion},
+    event::{self, messagepack_ext_types, rgb::Rgb},
+    neovim::{action::Action, button::Button, modifiers::Modifiers, Neovim},
+    rendering::{layout::Layout, pipelines::overlay::Rect, state::RenderState, Motion},
     rpc::{self, Notification},
-    text::{font::Metrics, fonts::FontSetting},
+    text::{font::Metrics, font_files, fonts::FontSetting, system_fonts},
     ui::{
         options::{FontSize, GuiFont},
-        Ui,
+        window::Window as UiWindow,
+        GridUnderCursor, Ui,
     },
     util::{
-        vec2::{PixelVec, Vec2},
+        vec2::{CellVec, PixelVec, Vec2},
         Values,
     },
     UserEvent,
 };
 use rmpv::Value;
 use std::{
+    env,
+    path::PathBuf,
+    process,
     sync::Arc,
+    thread,
     time::{Duration, Instant},
 };
 use winit::{
@@ -30,9 +48,9 @@ use winit::{
         DeviceEvent, DeviceId, ElementState, KeyEvent, MouseButton, MouseScrollDelta, StartCause,
         TouchPhase, WindowEvent,
     },
-    event_loop::{ActiveEventLoop, ControlFlow},
-    keyboard::{Key, ModifiersState, NamedKey},
-    window::{Fullscreen, Window, WindowId},
+    event_loop::{ActiveEventLoop, ControlFlow, EventLoopProxy},
+    keyboard::ModifiersState,
+    window::{CursorIcon, Fullscreen, Window, WindowId},
 };
 
 pub struct EventHandler {
@@ -42,9 +60,24 @@ pub struct EventHandler {
     settings: Settings,
     mouse: Mouse,
     modifiers: ModifiersState,
+    keyboard: Keyboard,
+    keybindings: Keybindings,
     neovim: Neovim,
     frame_number: u32,
     last_render_time: Option<Instant>,
+    /// The fonts to restore when a font preview ends
+    fonts_before_preview: Option<Vec<FontSetting>>,
+    /// The font height to restore when zooming is reset
+    font_height_before_zoom: Option<f32>,
+    /// Whether files are being dragged over the window
+    hovering_files: bool,
+    /// Files dropped on the window that are yet to be opened
+    dropped_files: Vec<PathBuf>,
+    /// The hyperlink under the mouse
+    hovered_link: Option<Link>,
+    proxy: EventLoopProxy<UserEvent>,
+    /// Watches the post-process shader for changes while one is set
+    post_process_watcher: Option<FileWatcher>,
 }
 
 impl ApplicationHandler<UserEvent> for EventHandler {
@@ -90,6 +123,21 @@ impl ApplicationHandler<UserEvent> for EventHandler {
                 self.redraw(event_loop);
             }
             WindowEvent::Focused(focus) => self.neovim.ui_set_focus(focus),
+            WindowEvent::HoveredFile(_) => {
+                self.hovering_files = true;
+                self.update_overlay();
+            }
+            WindowEvent::HoveredFileCancelled => {
+                self.hovering_files = false;
+                self.update_overlay();
+            }
+            WindowEvent::DroppedFile(path) => {
+                self.dropped_files.push(path);
+                if self.hovering_files {
+                    self.hovering_files = false;
+                    self.update_overlay();
+                }
+            }
             _ => {}
         }
     }
@@ -111,10 +159,17 @@ impl ApplicationHandler<UserEvent> for EventHandler {
             }
             UserEvent::Request(request) => self.request(request),
             UserEvent::Notification(notification) => self.notification(notification, event_loop),
+            UserEvent::PostProcessChanged => self.reload_post_process(),
         }
     }
 
-    fn about_to_wait(&mut self, _event_loop: &ActiveEventLoop) {}
+    fn about_to_wait(&mut self, _event_loop: &ActiveEventLoop) {
+        // Each dropped file arrives as a separate event, so they are
+        // collected to be handled together
+        if !self.dropped_files.is_empty() {
+            self.drop_files();
+        }
+    }
 
     fn device_event(
         &mut self,
@@ -132,7 +187,7 @@ impl ApplicationHandler<UserEvent> for EventHandler {
 }
 
 impl EventHandler {
-    pub fn new(neovim: Neovim, transparent: bool) -> Self {
+    pub fn new(neovim: Neovim, transparent: bool, proxy: EventLoopProxy<UserEvent>) -> Self {
         Self {
             window: None,
             render_state: None,
@@ -141,8 +196,17 @@ impl EventHandler {
             settings: Settings::new(transparent),
             mouse: Mouse::new(),
             modifiers: ModifiersState::default(),
+            keyboard: Keyboard::new(),
+            keybindings: Keybindings::new(),
             neovim,
             last_render_time: None,
+            fonts_before_preview: None,
+            font_height_before_zoom: None,
+            hovering_files: false,
+            dropped_files: vec![],
+            hovered_link: None,
+            proxy,
+            post_process_watcher: None,
         }
     }
 
@@ -163,6 +227,7 @@ impl EventHandler {
                     let mut args = Values::new(params.into_iter().next()?)?;
                     let height: f32 = args.next()?;
                     let size = FontSize::Height(height * self.window().scale_factor() as f32);
+                    self.font_height_before_zoom = None;
                     self.set_font_size(size);
                     self.finish_font_change();
                 }
@@ -171,6 +236,7 @@ impl EventHandler {
                     let mut args = Values::new(params.into_iter().next()?)?;
                     let width: f32 = args.next()?;
                     let size = FontSize::Width(width * self.window().scale_factor() as f32);
+                    self.font_height_before_zoom = None;
                     self.set_font_size(size);
                     self.finish_font_change();
                 }
@@ -189,14 +255,143 @@ impl EventHandler {
                     self.window().request_redraw();
                 }
 
+                "neophyte.set_cursor_effects" => {
+                    let mut args = Values::new(params.into_iter().next()?)?;
+                    self.settings.cursor_effects = args.next()?;
+                    self.window().request_redraw();
+                }
+
+                "neophyte.set_window_offset" => {
+                    let mut args = Values::new(params.into_iter().next()?)?;
+                    let win: messagepack_ext_types::Window = args.next()?;
+                    let x = args.next()?;
+                    let y = args.next()?;
+                    let transform = self
+                        .settings
+                        .window_transforms
+                        .entry(win.number()?)
+                        .or_default();
+                    transform.offset = PixelVec::new(x, y);
+                    self.window().request_redraw();
+                }
+
+                "neophyte.set_window_opacity" => {
+                    let mut args = Values::new(params.into_iter().next()?)?;
+                    let win: messagepack_ext_types::Window = args.next()?;
+                    let opacity: f32 = args.next()?;
+                    let transform = self
+                        .settings
+                        .window_transforms
+                        .entry(win.number()?)
+                        .or_default();
+                    transform.opacity = opacity.clamp(0., 1.);
+                    self.window().request_redraw();
+                }
+
+                "neophyte.set_window_scale" => {
+                    let mut args = Values::new(params.into_iter().next()?)?;
+                    let win: messagepack_ext_types::Window = args.next()?;
+                    let scale: f32 = args.next()?;
+                    let transform = self
+                        .settings
+                        .window_transforms
+                        .entry(win.number()?)
+                        .or_default();
+                    transform.scale = scale.max(0.);
+                    self.window().request_redraw();
+                }
+
+                "neophyte.set_float_style" => {
+                    let mut args = Values::new(params.into_iter().next()?)?;
+                    self.settings.float_style = args.next()?;
+                    self.window().request_redraw();
+                }
+
+                "neophyte.set_window_style" => {
+                    let mut args = Values::new(params.into_iter().next()?)?;
+                    let win: messagepack_ext_types::Window = args.next()?;
+                    let style = args.next()?;
+                    self.settings.window_styles.insert(win.number()?, style);
+                    self.window().request_redraw();
+                }
+
+                "neophyte.unset_window_style" => {
+                    let mut args = Values::new(params.into_iter().next()?)?;
+                    let win: messagepack_ext_types::Window = args.next()?;
+                    self.settings.window_styles.remove(&win.number()?);
+                    self.window().request_redraw();
+                }
+
+                "neophyte.set_dim_inactive" => {
+                    let mut args = Values::new(params.into_iter().next()?)?;
+                    self.settings.dim_inactive = args.next()?;
+                    self.window().request_redraw();
+                }
+
+                "neophyte.set_window_animation" => {
+                    let mut args = Values::new(params.into_iter().next()?)?;
+                    self.settings.window_animation = args.next()?;
+                    self.window().request_redraw();
+                }
+
+                "neophyte.set_easing" => {
+                    let mut args = Values::new(params.into_iter().next()?)?;
+                    self.settings.easing = args.next()?;
+                    self.window().request_redraw();
+                }
+
+                "neophyte.set_pixel_scroll" => {
+                    let mut args = Values::new(params.into_iter().next()?)?;
+                    self.settings.pixel_scroll = args.next()?;
+                }
+
                 "neophyte.set_fonts" => {
                     let args = Values::new(params.into_iter().next()?)?;
                     let font_settings = args.map()?;
                     let em = self.metrics().em;
+                    self.fonts_before_preview = None;
+                    self.set_fonts(font_settings, FontSize::Height(em));
+                    self.finish_font_change();
+                }
+
+                "neophyte.preview_font" => {
+                    let mut args = Values::new(params.into_iter().next()?)?;
+                    let preview: FontSetting = args.next()?;
+                    let committed = self.fonts_before_preview.get_or_insert_with(|| {
+                        self.render_state
+                            .as_ref()
+                            .unwrap()
+                            .fonts
+                            .families()
+                            .map(|family| family.setting.clone())
+                            .collect()
+                    });
+                    let font_settings = std::iter::once(preview)
+                        .chain(committed.iter().cloned())
+                        .collect();
+                    let em = self.metrics().em;
                     self.set_fonts(font_settings, FontSize::Height(em));
                     self.finish_font_change();
                 }
 
+                "neophyte.end_font_preview" => {
+                    if let Some(font_settings) = self.fonts_before_preview.take() {
+                        let em = self.metrics().em;
+                        self.set_fonts(font_settings, FontSize::Height(em));
+                        self.finish_font_change();
+                    }
+                }
+
+                "neophyte.set_font_dirs" => {
+                    let args = Values::new(params.into_iter().next()?)?;
+                    let dirs: Vec<String> = args.map()?;
+                    self.render_state.as_mut().unwrap().fonts.set_font_dirs(
+                        dirs.iter()
+                            .map(|dir| font_files::expand_home(dir))
+                            .collect(),
+                    );
+                }
+
                 "neophyte.set_underline_offset" => {
                     let mut args = Values::new(params.into_iter().next()?)?;
                     let offset: f32 = args.next()?;
@@ -205,6 +400,14 @@ impl EventHandler {
                     self.window().request_redraw();
                 }
 
+                "neophyte.set_letter_spacing" => {
+                    let mut args = Values::new(params.into_iter().next()?)?;
+                    let spacing: f32 = args.next()?;
+                    self.settings.letter_spacing = spacing;
+                    self.update_spacing();
+                    self.finish_font_change();
+                }
+
                 "neophyte.set_render_size" => {
                     let mut args = Values::new(params.into_iter().next()?)?;
                     let width = args.next()?;
@@ -213,11 +416,43 @@ impl EventHandler {
                     self.resize();
                 }
 
+                "neophyte.set_post_process" => {
+                    let mut args = Values::new(params.into_iter().next()?)?;
+                    let path: PathBuf = args.next::<String>()?.into();
+                    self.settings.animate_post_process = args.next()?;
+                    self.post_process_watcher =
+                        Some(FileWatcher::new(path.clone(), self.proxy.clone(), || {
+                            UserEvent::PostProcessChanged
+                        }));
+                    let result = self
+                        .render_state
+                        .as_mut()
+                        .unwrap()
+                        .set_post_process(Some(path));
+                    if let Err(e) = result {
+                        self.report_error(e.to_string());
+                    }
+                    self.window().request_redraw();
+                }
+
+                "neophyte.unset_post_process" => {
+                    self.post_process_watcher = None;
+                    let _ = self.render_state.as_mut().unwrap().set_post_process(None);
+                    self.window().request_redraw();
+                }
+
                 "neophyte.unset_render_size" => {
                     self.settings.render_size = None;
                     self.resize();
                 }
 
+                "neophyte.set_layout" => {
+                    let mut args = Values::new(params.into_iter().next()?)?;
+                    self.settings.layout = args.next()?;
+                    self.resize();
+                    self.window().request_redraw();
+                }
+
                 "neophyte.start_render" => {
                     let mut args = Values::new(params.into_iter().next()?)?;
                     let path: String = args.next()?;
@@ -244,6 +479,40 @@ impl EventHandler {
                     self.window().set_fullscreen(fullscreen);
                 }
 
+                "neophyte.map" => {
+                    let mut args = Values::new(params.into_iter().next()?)?;
+                    let keys: String = args.next()?;
+                    let binding: Option<Binding> = args.next();
+                    self.keybindings.set(&keys, binding);
+                }
+
+                "neophyte.set_default_keybindings" => {
+                    let mut args = Values::new(params.into_iter().next()?)?;
+                    self.keybindings.use_defaults = args.next()?;
+                }
+
+                "neophyte.set_multiclick_interval" => {
+                    let mut args = Values::new(params.into_iter().next()?)?;
+                    let ms: u64 = args.next()?;
+                    self.settings.multiclick_interval = Duration::from_millis(ms);
+                }
+
+                "neophyte.set_pointer" => {
+                    let mut args = Values::new(params.into_iter().next()?)?;
+                    let pointer: PointerSettings = args.next()?;
+                    self.settings.pointer = pointer;
+                    if !pointer.hide_while_typing {
+                        self.show_pointer();
+                    }
+                    // Pick the icon again on the next move
+                    self.mouse.hovered = None;
+                }
+
+                "neophyte.enable_drop_handler" => self.settings.drop_handler = true,
+                "neophyte.disable_drop_handler" => self.settings.drop_handler = false,
+                "neophyte.enable_link_handler" => self.settings.link_handler = true,
+                "neophyte.disable_link_handler" => self.settings.link_handler = false,
+
                 "neophyte.leave" => event_loop.exit(),
                 "neophyte.buf_leave" => self.ui.ignore_next_scroll = true,
                 "neophyte.enable_raw_input" => self.settings.raw_input = true,
@@ -268,6 +537,9 @@ impl EventHandler {
                 Ok(events) => {
                     for event in events.into_iter() {
                         log::debug!("{event:?}");
+                        if let event::Event::WinClose(event::WinClose { grid }) = &event {
+                            self.forget_window(*grid);
+                        }
                         self.ui.process(event);
                     }
                 }
@@ -282,6 +554,7 @@ impl EventHandler {
         if self.ui.did_flush {
             if let Some(guifont_update) = self.ui.guifont_update.take() {
                 let GuiFont { fonts, size } = guifont_update;
+                self.fonts_before_preview = None;
                 self.set_fonts(
                     fonts.into_iter().map(FontSetting::with_name).collect(),
                     size,
@@ -289,6 +562,25 @@ impl EventHandler {
                 self.finish_font_change();
             }
 
+            if let Some(linespace) = self.ui.linespace_update.take() {
+                self.settings.linespace = linespace as f32;
+                self.update_spacing();
+                self.finish_font_change();
+            }
+
+            if let Some(guifontwide_update) = self.ui.guifontwide_update.take() {
+                let em = self.metrics().em;
+                self.render_state.as_mut().unwrap().fonts.set_wide_fonts(
+                    guifontwide_update
+                        .fonts
+                        .into_iter()
+                        .map(FontSetting::with_name)
+                        .collect(),
+                    FontSize::Height(em),
+                );
+                self.finish_font_change();
+            }
+
             let bg_override = if self.settings.transparent {
                 self.settings.bg_override
             } else {
@@ -300,6 +592,8 @@ impl EventHandler {
                 .unwrap()
                 .update(&self.ui, bg_override);
             self.ui.clear_dirty();
+            // Text under the mouse may have changed
+            self.update_hovered_link();
             self.window().request_redraw();
         }
         log::debug!("Neovim redraw end");
@@ -331,6 +625,16 @@ impl EventHandler {
                     .send_response(rpc::Response::result(msgid, names));
             }
 
+            "neophyte.list_system_fonts" => {
+                // Reading every installed font is slow enough to stall
+                // rendering, so respond from another thread
+                let neovim = self.neovim.clone();
+                thread::spawn(move || {
+                    let families = system_fonts::list().into_iter().map(Value::from).collect();
+                    neovim.send_response(rpc::Response::result(msgid, Value::Array(families)));
+                });
+            }
+
             "neophyte.get_cursor_speed" => {
                 let cursor_speed = self.settings.cursor_speed;
                 self.neovim
@@ -361,6 +665,18 @@ impl EventHandler {
                     .send_response(rpc::Response::result(msgid, offset.into()));
             }
 
+            "neophyte.get_letter_spacing" => {
+                let spacing = self.settings.letter_spacing;
+                self.neovim
+                    .send_response(rpc::Response::result(msgid, spacing.into()));
+            }
+
+            "neophyte.get_multiclick_interval" => {
+                let ms = self.settings.multiclick_interval.as_millis() as u64;
+                self.neovim
+                    .send_response(rpc::Response::result(msgid, ms.into()));
+            }
+
             "neophyte.get_render_size" => {
                 let render_size = self.render_size();
                 self.neovim.send_response(rpc::Response::result(
@@ -385,140 +701,328 @@ impl EventHandler {
     fn keyboard_input(&mut self, event: &KeyEvent) {
         match event.state {
             ElementState::Pressed => {}
-            ElementState::Released => return,
+            ElementState::Released => {
+                self.keyboard.release(&event.logical_key);
+                return;
+            }
         }
 
         log::info!("Got keyboard input: {event:?}");
-        match &event.logical_key {
-            Key::Named(key) => {
-                let c = || {
-                    use NamedKey::*;
-                    Some(match key {
-                        Enter => "Enter",
-                        Tab => "Tab",
-                        Space => "Space",
-                        ArrowDown => "Down",
-                        ArrowLeft => "Left",
-                        ArrowRight => "Right",
-                        ArrowUp => "Up",
-                        End => "End",
-                        Home => "Home",
-                        PageDown => "PageDown",
-                        PageUp => "PageUp",
-                        Backspace => "BS",
-                        Delete => "Del",
-                        Escape => "Esc",
-                        F1 => "F1",
-                        F2 => "F2",
-                        F3 => "F3",
-                        F4 => "F4",
-                        F5 => "F5",
-                        F6 => "F6",
-                        F7 => "F7",
-                        F8 => "F8",
-                        F9 => "F9",
-                        F10 => "F10",
-                        F11 => "F11",
-                        F12 => "F12",
-                        F13 => "F13",
-                        F14 => "F14",
-                        F15 => "F15",
-                        F16 => "F16",
-                        F17 => "F17",
-                        F18 => "F18",
-                        F19 => "F19",
-                        F20 => "F20",
-                        F21 => "F21",
-                        F22 => "F22",
-                        F23 => "F23",
-                        F24 => "F24",
-                        F25 => "F25",
-                        F26 => "F26",
-                        F27 => "F27",
-                        F28 => "F28",
-                        F29 => "F29",
-                        F30 => "F30",
-                        F31 => "F31",
-                        F32 => "F32",
-                        F33 => "F33",
-                        F34 => "F34",
-                        F35 => "F35",
-                        _ => return None,
-                    })
-                };
-
-                if let Some(c) = c() {
-                    self.send_keys(c, false);
+        match self.keyboard.input(&KeyPress::from(event), self.modifiers) {
+            Some(Encoded::Keys(keys)) => {
+                self.hide_pointer();
+                match self.keybindings.get(&keys).cloned() {
+                    Some(binding) => self.run_binding(binding),
+                    None => self.send_keys(keys),
                 }
             }
-
-            Key::Character(c) => {
-                let s = match c.as_str() {
-                    "<" => "Lt",
-                    "\\" => "Bslash",
-                    "|" => "Bar",
-                    _ => c.as_str(),
-                };
-                self.send_keys(s, true);
-            }
-
-            Key::Unidentified(_) | Key::Dead(_) => {}
+            Some(Encoded::Other(keys)) => self.send_raw_input(keys),
+            None => {}
         }
     }
 
     fn cursor_moved(&mut self, position: PhysicalPosition<f64>) {
         log::info!("Got cursor move: {position:?}");
+        self.show_pointer();
         let position: PixelVec<f64> = position.into();
         let position = position.cast_as::<i64>();
         let cell_size = self.cell_size();
-        let surface_size = self.render_state.as_ref().unwrap().surface_size();
-        let inner = (surface_size.into_cells(cell_size)).into_pixels(cell_size);
-        let margin = (surface_size - inner) / 2;
-        let position = position - margin.cast();
-        let Ok(position) = position.try_cast::<u32>() else {
+        let Some(position) = self.layout().to_grid(position) else {
             return;
         };
         self.mouse.position = position;
-        if let Some(grid) = self.ui.grid_under_cursor(position, self.cell_size().cast()) {
-            let position = position.into_cells(cell_size);
-            self.neovim.input_mouse(
-                self.mouse.buttons.first().unwrap_or(Button::Move),
-                // Irrelevant for move
-                Action::ButtonDrag,
-                self.modifiers.into(),
-                grid.grid,
-                position.0.y,
-                position.0.x,
+        let Some(grid) = self.ui.grid_under_cursor(position, cell_size) else {
+            self.mouse.hovered = None;
+            self.update_hovered_link();
+            self.set_pointer(self.settings.pointer.chrome);
+            return;
+        };
+        // Neovim only needs to know when the mouse enters a different cell
+        let previous = self.mouse.hovered.replace(grid);
+        if previous == Some(grid) {
+            return;
+        }
+        if self.hovering_files && previous.map(|previous| previous.grid) != Some(grid.grid) {
+            self.update_overlay();
+        }
+        self.update_hovered_link();
+        if self.hovered_link.is_none() {
+            let region = Region::under_cursor(&self.ui, grid);
+            self.set_pointer(self.settings.pointer.icon(region));
+        }
+        if !self.ui.mouse {
+            return;
+        }
+
+        let button = self.mouse.buttons.first();
+        let entered_grid = previous.map(|previous| previous.grid) != Some(grid.grid);
+        if self.ui.mousefocus && button.is_none() && entered_grid {
+            self.focus_grid(grid.grid);
+        }
+
+        let button = match button {
+            Some(button) => button,
+            None if self.ui.mousemoveevent => Button::Move,
+            None => return,
+        };
+        self.neovim.input_mouse(
+            button,
+            // Irrelevant for move
+            Action::ButtonDrag,
+            self.modifiers.into(),
+            grid.grid,
+            grid.position.0.y,
+            grid.position.0.x,
+        );
+    }
+
+    /// Open the dropped files, or pass them to the Lua drop handler
+    fn drop_files(&mut self) {
+        let paths: Vec<Value> = std::mem::take(&mut self.dropped_files)
+            .into_iter()
+            .map(|path| path.to_string_lossy().into_owned().into())
+            .collect();
+        let grid = self
+            .ui
+            .grid_under_cursor(self.mouse.position, self.cell_size())
+            .filter(|_| self.ui.mouse);
+
+        if self.settings.drop_handler {
+            let target = match grid {
+                Some(grid) => {
+                    let mut target = vec![
+                        ("grid".into(), grid.grid.into()),
+                        ("row".into(), grid.position.0.y.into()),
+                        ("col".into(), grid.position.0.x.into()),
+                    ];
+                    if let Some(win) = self.ui.window_handles.get(&grid.grid) {
+                        target.push(("win".into(), win.clone().into()));
+                    }
+                    Value::Map(target)
+                }
+                None => Value::Nil,
+            };
+            self.neovim.exec_lua(
+                "require('neophyte').receive_drop(...)".to_string(),
+                vec![paths.into(), target],
+            );
+        } else {
+            if let Some(grid) = grid {
+                self.focus_grid(grid.grid);
+            }
+            self.neovim
+                .exec_lua(DROP_LUA.to_string(), vec![paths.into()]);
+        }
+    }
+
+    /// Paint the highlights that go over the frame
+    fn update_overlay(&mut self) {
+        let cell_size = self.cell_size();
+        let mut rects = vec![];
+        if self.hovering_files {
+            let target = self
+                .ui
+                .grid_under_cursor(self.mouse.position, cell_size)
+                .and_then(|grid| {
+                    let position = self.ui.position(grid.grid)?;
+                    let size = self.ui.grid(grid.grid)?.contents().size;
+                    Some((position, size))
+                });
+            if let Some((position, size)) = target {
+                let cell_size = cell_size.cast_as::<f32>();
+                let fg = self.ui.default_colors.rgb_fg.unwrap_or(Rgb::WHITE);
+                let rect = Rect {
+                    position: position.into_pixels(cell_size).0,
+                    size: size.cast_as::<f32>().into_pixels(cell_size).0,
+                    color: fg.into_srgb(0.1),
+                };
+                rects.push(rect);
+                rects.extend(
+                    Rect {
+                        color: fg.into_srgb(0.6),
+                        ..rect
+                    }
+                    .outline(2. * self.window().scale_factor() as f32),
+                );
+            }
+        }
+        if let Some(link) = &self.hovered_link {
+            if let Some(position) = self.ui.position(link.grid) {
+                let cell_size = cell_size.cast_as::<f32>();
+                let thickness = self.window().scale_factor().round().max(1.) as f32;
+                let start =
+                    position + CellVec::new(link.columns.start as f32, link.row as f32 + 1.);
+                let mut position = start.into_pixels(cell_size).0;
+                position.y -= thickness;
+                let fg = self.ui.default_colors.rgb_fg.unwrap_or(Rgb::WHITE);
+                rects.push(Rect {
+                    position,
+                    size: Vec2::new(link.columns.len() as f32 * cell_size.x, thickness),
+                    color: fg.into_srgb(1.),
+                });
+            }
+        }
+        self.render_state.as_mut().unwrap().set_overlay(&rects);
+        self.window().request_redraw();
+    }
+
+    /// Find the hyperlink under the mouse, underlining it and changing the
+    /// pointer to show that it can be clicked
+    fn update_hovered_link(&mut self) {
+        let link = self
+            .mouse
+            .hovered
+            .and_then(|grid| links::link_at(&self.ui, grid));
+        if link == self.hovered_link {
+            return;
+        }
+        let icon = match (&link, self.mouse.hovered) {
+            (Some(_), _) => CursorIcon::Pointer,
+            (None, Some(grid)) => self
+                .settings
+                .pointer
+                .icon(Region::under_cursor(&self.ui, grid)),
+            (None, None) => self.settings.pointer.chrome,
+        };
+        self.set_pointer(icon);
+        self.hovered_link = link;
+        self.update_overlay();
+    }
+
+    /// Show an error message in Neovim
+    fn report_error(&mut self, message: String) {
+        log::error!("{message}");
+        self.neovim
+            .exec_lua(NOTIFY_ERROR_LUA.to_string(), vec![message.into()]);
+    }
+
+    /// Recompile the post-process shader after its file changed, keeping the
+    /// previous version if the new one has errors
+    fn reload_post_process(&mut self) {
+        let Some(render_state) = self.render_state.as_mut() else {
+            return;
+        };
+        match render_state.reload_post_process() {
+            Ok(true) => {
+                log::info!("Reloaded post-process shader");
+                self.window().request_redraw();
+            }
+            Ok(false) => {}
+            Err(e) => self.report_error(e.to_string()),
+        }
+    }
+
+    /// Open the URL, or pass it to the Lua link handler
+    fn open_link(&mut self, url: String) {
+        if self.settings.link_handler {
+            self.neovim.exec_lua(
+                "require('neophyte').receive_link(...)".to_string(),
+                vec![url.into()],
             );
+        } else if let Err(e) = links::open(&url) {
+            log::error!("Failed to open {url}: {e}");
+        }
+    }
+
+    fn set_pointer(&mut self, icon: CursorIcon) {
+        if self.mouse.icon != icon {
+            self.mouse.icon = icon;
+            self.window().set_cursor(icon);
+        }
+    }
+
+    fn hide_pointer(&mut self) {
+        if self.settings.pointer.hide_while_typing && !self.mouse.hidden {
+            self.mouse.hidden = true;
+            self.window().set_cursor_visible(false);
+        }
+    }
+
+    fn show_pointer(&mut self) {
+        if self.mouse.hidden {
+            self.mouse.hidden = false;
+            self.window().set_cursor_visible(true);
+        }
+    }
+
+    /// Drop what plugins have set for the window of the given grid
+    fn forget_window(&mut self, grid: u32) {
+        let win = self
+            .ui
+            .window_handles
+            .get(&grid)
+            .and_then(|win| win.number());
+        if let Some(win) = win {
+            self.settings.window_transforms.remove(&win);
+            self.settings.window_styles.remove(&win);
+        }
+    }
+
+    /// Make the window containing the grid the current window, as for the
+    /// mousefocus option
+    fn focus_grid(&mut self, grid: u32) {
+        let focusable = match self.ui.grid(grid).map(|grid| grid.window()) {
+            Some(UiWindow::Normal(_)) => true,
+            Some(UiWindow::Floating(window)) => window.focusable,
+            _ => false,
+        };
+        if !focusable {
+            return;
+        }
+        if let Some(win) = self.ui.window_handles.get(&grid) {
+            self.neovim.set_current_win(win.clone());
         }
     }
 
     fn mouse_input(&mut self, state: ElementState, button: MouseButton) {
         log::info!("Got mouse input: {button:?}, {state:?}");
+        self.show_pointer();
         let Ok(button) = button.try_into() else {
             return;
         };
 
         let action = state.into();
-        let depressed = match action {
-            Action::ButtonPress => true,
-            Action::ButtonRelease => false,
-            _ => unreachable!(),
-        };
-        match button {
-            Button::Left => self.mouse.buttons = self.mouse.buttons.with_left(depressed),
-            Button::Right => self.mouse.buttons = self.mouse.buttons.with_right(depressed),
-            Button::Middle => self.mouse.buttons = self.mouse.buttons.with_middle(depressed),
-            _ => unreachable!(),
+        self.mouse.buttons = self
+            .mouse
+            .buttons
+            .with(button, action == Action::ButtonPress);
+        // Ctrl+click opens links. Neovim sees neither the press nor the
+        // release.
+        if button == Button::Left {
+            match action {
+                Action::ButtonPress if self.modifiers.control_key() => {
+                    if let Some(link) = &self.hovered_link {
+                        self.mouse.opened_link = true;
+                        self.open_link(link.url.clone());
+                        return;
+                    }
+                }
+                Action::ButtonRelease if std::mem::take(&mut self.mouse.opened_link) => return,
+                _ => {}
+            }
+        }
+        if !self.ui.mouse {
+            return;
         }
         if let Some(grid) = self
             .ui
             .grid_under_cursor(self.mouse.position, self.cell_size().cast())
         {
+            let mut modifiers: Modifiers = self.modifiers.into();
+            if action == Action::ButtonPress {
+                let clicks = self.mouse.clicks.press(
+                    button,
+                    grid.grid,
+                    grid.position,
+                    Instant::now(),
+                    self.settings.multiclick_interval,
+                );
+                modifiers = modifiers.with_clicks(clicks);
+            }
             self.neovim.input_mouse(
                 button,
                 action,
-                self.modifiers.into(),
+                modifiers,
                 grid.grid,
                 grid.position.0.y,
                 grid.position.0.x,
@@ -533,7 +1037,7 @@ impl EventHandler {
             TouchPhase::Started | TouchPhase::Ended | TouchPhase::Cancelled
         );
 
-        let (delta, kind) = match delta {
+        let (mut delta, kind): (Vec2<f64>, _) = match delta {
             MouseScrollDelta::LineDelta(horizontal, vertical) => {
                 (Vec2::new(horizontal, vertical).cast(), ScrollKind::Lines)
             }
@@ -541,6 +1045,11 @@ impl EventHandler {
             MouseScrollDelta::PixelDelta(delta) => (delta.into(), ScrollKind::Pixels),
         };
 
+        if matches!(kind, ScrollKind::Pixels) && self.settings.pixel_scroll && self.ui.mouse {
+            self.track_scroll(delta.y, phase);
+            delta.y = 0.;
+        }
+
         let modifiers = self.modifiers.into();
 
         let delta: Vec2<i32> = delta.cast_as();
@@ -566,6 +1075,10 @@ impl EventHandler {
             return;
         };
 
+        if !self.ui.mouse {
+            return;
+        }
+
         let action = if lines.y < 0 {
             Action::WheelDown
         } else {
@@ -601,6 +1114,41 @@ impl EventHandler {
         }
     }
 
+    /// Move the grid under the mouse with the trackpad, scrolling Neovim to
+    /// keep up
+    fn track_scroll(&mut self, delta: f64, phase: TouchPhase) {
+        let Some(grid) = self
+            .ui
+            .grid_under_cursor(self.mouse.position, self.cell_size())
+        else {
+            return;
+        };
+        let cells = delta as f32 / self.cell_size().y as f32;
+        let render_state = self.render_state.as_mut().unwrap();
+        render_state.track_scroll(grid.grid, cells);
+        if matches!(phase, TouchPhase::Ended | TouchPhase::Cancelled) {
+            render_state.release_scroll();
+        }
+        self.request_scroll_lines();
+        self.window().request_redraw();
+    }
+
+    /// Scroll Neovim by the lines that trackpad scrolling needs. This sets the
+    /// window's top line directly rather than sending wheel events, which
+    /// scroll by however many lines the mousescroll option says, or keys,
+    /// which depend on the mode.
+    fn request_scroll_lines(&mut self) {
+        for (grid, lines) in self.render_state.as_mut().unwrap().scroll_requests() {
+            let Some(win) = self.ui.window_handles.get(&grid) else {
+                continue;
+            };
+            self.neovim.exec_lua(
+                SCROLL_LUA.to_string(),
+                vec![win.clone().into(), lines.into()],
+            );
+        }
+    }
+
     fn resized(&mut self, physical_size: PhysicalSize<u32>) {
         log::info!("Got resize: {physical_size:?}");
         self.resize();
@@ -610,6 +1158,7 @@ impl EventHandler {
         log::info!("Got rescale: {new_scale_factor}");
         let new_font_size = FontSize::Height(self.metrics().em * new_scale_factor as f32);
         self.set_font_size(new_font_size);
+        self.update_spacing();
     }
 
     fn redraw(&mut self, event_loop: &ActiveEventLoop) {
@@ -640,6 +1189,7 @@ impl EventHandler {
             self.window.as_ref().unwrap(),
             self.frame_number,
         );
+        self.request_scroll_lines();
 
         if self.settings.send_frame_events {
             self.neovim.exec_lua(
@@ -665,32 +1215,81 @@ impl EventHandler {
         }
     }
 
-    fn send_keys(&mut self, c: &str, ignore_shift: bool) {
-        let shift = self.modifiers.shift_key() && !ignore_shift;
-        let ctrl = self.modifiers.control_key();
-        let alt = self.modifiers.alt_key();
-        let logo = self.modifiers.super_key();
-        let c = if ctrl || alt || logo || shift {
-            let ctrl = if ctrl { "C" } else { "" };
-            let shift = if shift { "S" } else { "" };
-            let alt = if alt { "A" } else { "" };
-            let logo = if logo { "D" } else { "" };
-            format!("<{ctrl}{shift}{alt}{logo}-{c}>")
-        } else {
-            match c.len() {
-                1 => c.to_string(),
-                _ => format!("<{c}>"),
+    fn run_binding(&mut self, binding: Binding) {
+        match binding {
+            Binding::Action(action) => self.gui_action(action),
+            Binding::Input(keys) => self.send_keys(keys),
+            Binding::Lua(keys) => self.neovim.exec_lua(
+                "require('neophyte').receive_keybinding(...)".to_string(),
+                vec![keys.into()],
+            ),
+        }
+    }
+
+    fn gui_action(&mut self, action: GuiAction) {
+        match action {
+            GuiAction::ToggleFullscreen => {
+                let fullscreen = self
+                    .window()
+                    .fullscreen()
+                    .is_none()
+                    .then_some(Fullscreen::Borderless(None));
+                self.window().set_fullscreen(fullscreen);
             }
-        };
 
+            GuiAction::ZoomIn | GuiAction::ZoomOut => {
+                let em = self.metrics().em;
+                self.font_height_before_zoom.get_or_insert(em);
+                let step = self.window().scale_factor() as f32;
+                let em = if action == GuiAction::ZoomIn {
+                    em + step
+                } else {
+                    (em - step).max(step)
+                };
+                self.set_font_size(FontSize::Height(em));
+                self.finish_font_change();
+            }
+
+            GuiAction::ZoomReset => {
+                if let Some(em) = self.font_height_before_zoom.take() {
+                    self.set_font_size(FontSize::Height(em));
+                    self.finish_font_change();
+                }
+            }
+
+            GuiAction::Copy => self
+                .neovim
+                .exec_lua("require('neophyte').copy()".to_string(), vec![]),
+
+            GuiAction::Paste => self
+                .neovim
+                .exec_lua("require('neophyte').paste()".to_string(), vec![]),
+
+            GuiAction::NewWindow => {
+                // Keep the options but not the Neovim command, which may
+                // include files to open
+                let args = env::args().skip(1).take_while(|arg| arg != "--");
+                let result = env::current_exe()
+                    .and_then(|exe| process::Command::new(exe).args(args).spawn());
+                if let Err(e) = result {
+                    log::error!("Failed to open a new window: {e}");
+                }
+            }
+        }
+    }
+
+    fn send_keys(&mut self, keys: String) {
+        self.send_raw_input(keys.clone());
+        self.neovim.input(keys);
+    }
+
+    fn send_raw_input(&mut self, keys: String) {
         if self.settings.raw_input {
             self.neovim.exec_lua(
                 "require('neophyte').receive_raw_input(...)".to_string(),
-                vec![c.clone().into()],
+                vec![keys.into()],
             );
         }
-
-        self.neovim.input(c);
     }
 
     fn set_font_size(&mut self, size: FontSize) {
@@ -713,6 +1312,15 @@ impl EventHandler {
             .set_fonts(fonts, size)
     }
 
+    /// Apply the line and letter spacing settings in physical pixels
+    fn update_spacing(&mut self) {
+        let scale_factor = self.window().scale_factor() as f32;
+        self.render_state.as_mut().unwrap().fonts.set_spacing(
+            self.settings.linespace * scale_factor,
+            self.settings.letter_spacing * scale_factor,
+        );
+    }
+
     fn cell_size(&self) -> Vec2<u32> {
         self.render_state.as_ref().unwrap().fonts.cell_size()
     }
@@ -725,19 +1333,25 @@ impl EventHandler {
     fn resize(&mut self) {
         let render_size = self.render_size();
         self.resize_neovim_grid();
-        let cell_size = self.cell_size();
-        self.render_state.as_mut().unwrap().resize(
-            render_size,
-            cell_size,
-            self.settings.transparent,
-        );
+        let layout = self.layout();
+        self.render_state
+            .as_mut()
+            .unwrap()
+            .resize(render_size, layout, self.settings.transparent);
     }
 
     fn resize_neovim_grid(&mut self) {
-        let size = self.render_size().into_cells(self.cell_size());
+        let size = self.layout().cells(self.cell_size());
         self.neovim.ui_try_resize_grid(1, size.0.x, size.0.y);
     }
 
+    /// Where the grid goes in the window
+    /// Where the grid is drawn. Hit-testing must use this too so that mouse
+    /// cells line up with rendering.
+    fn layout(&mut self) -> Layout {
+        Layout::new(self.render_size(), self.cell_size(), self.settings.layout)
+    }
+
     fn render_size(&mut self) -> PixelVec<u32> {
         if let Some(size) = self.settings.render_size {
             size
@@ -747,11 +1361,42 @@ impl EventHandler {
     }
 }
 
+/// Opens the dropped files given as a list of paths, escaping them for the
+/// command line
+const DROP_LUA: &str = "\
+local paths = ...
+local escaped = vim.tbl_map(vim.fn.fnameescape, paths)
+local ok, err = pcall(vim.cmd, 'drop ' .. table.concat(escaped, ' '))
+if not ok then
+  vim.notify(err, vim.log.levels.ERROR)
+end";
+
+const SCROLL_LUA: &str = "\
+local win, lines = ...
+if not vim.api.nvim_win_is_valid(win) then
+  return
+end
+vim.api.nvim_win_call(win, function()
+  local last = vim.api.nvim_buf_line_count(0)
+  local topline = math.max(1, math.min(vim.fn.line('w0') + lines, last))
+  vim.fn.winrestview({ topline = topline })
+end)";
+
+const NOTIFY_ERROR_LUA: &str = "vim.notify(..., vim.log.levels.ERROR)";
+
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
 struct Mouse {
     position: PixelVec<u32>,
     scroll: Vec2<i32>,
     buttons: Buttons,
+    clicks: Clicks,
+    /// The cell under the mouse when it last moved
+    hovered: Option<GridUnderCursor>,
+    icon: CursorIcon,
+    /// Whether the pointer is hidden while typing
+    hidden: bool,
+    /// Whether the left button was pressed to open a link
+    opened_link: bool,
 }
 
 impl Mouse {
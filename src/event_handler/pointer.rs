@@ -0,0 +1,146 @@
+//! Chooses the mouse pointer icon for the part of the UI under the mouse
+
+use crate::{
+    ui::{window::Window, GridUnderCursor, Ui},
+    util::{parse_map, vec2::CellVec, MaybeInto, Parse},
+};
+use rmpv::Value;
+use std::str::FromStr;
+use winit::window::CursorIcon;
+
+/// A part of the UI with its own pointer icon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Region {
+    /// Buffer contents and other text
+    Text,
+    /// Statuslines, winbars, floating window borders, and the like
+    Chrome,
+    /// A separator between vertical splits
+    VerticalSeparator,
+    /// A statusline or separator between horizontal splits
+    HorizontalSeparator,
+}
+
+impl Region {
+    /// Find the part of the UI under the mouse
+    pub fn under_cursor(ui: &Ui, grid: GridUnderCursor) -> Self {
+        // With multigrid, window chrome and the separators between windows
+        // are drawn on the default grid rather than the window grids
+        if grid.grid == 1 {
+            return Self::on_default_grid(ui, grid.position);
+        }
+
+        let Some(window_grid) = ui.grid(grid.grid) else {
+            return Self::Chrome;
+        };
+        match window_grid.window() {
+            Window::Normal(_) | Window::Floating(_) => {
+                let size = window_grid.contents().size.cast();
+                let in_margins = ui
+                    .viewport_margins
+                    .get(&grid.grid)
+                    .is_some_and(|margins| margins.contains(grid.position, size));
+                if in_margins {
+                    Self::Chrome
+                } else {
+                    Self::Text
+                }
+            }
+            Window::Messages { .. } => Self::Text,
+            Window::None | Window::External => Self::Chrome,
+        }
+    }
+
+    fn on_default_grid(ui: &Ui, position: CellVec<u32>) -> Self {
+        let CellVec(position) = position;
+        let windows: Vec<_> = ui
+            .draw_order
+            .iter()
+            .filter_map(|item| match ui.grid(item.grid)?.window() {
+                Window::Normal(window) => {
+                    let CellVec(start) = window.start.cast::<u32>();
+                    let CellVec(size) = window.size.cast::<u32>();
+                    Some((start, start + size))
+                }
+                _ => None,
+            })
+            .collect();
+
+        for &(start, end) in windows.iter() {
+            let in_rows = (start.y..end.y).contains(&position.y);
+            let in_columns = (start.x..end.x).contains(&position.x);
+            if in_rows && position.x == end.x {
+                return Self::VerticalSeparator;
+            }
+            if in_columns && position.y == end.y {
+                // The statusline can be dragged to resize the window if there
+                // is another window below it
+                let has_window_below = windows.iter().any(|&(other_start, other_end)| {
+                    other_start.y == end.y + 1 && other_start.x < end.x && start.x < other_end.x
+                });
+                return if has_window_below {
+                    Self::HorizontalSeparator
+                } else {
+                    Self::Chrome
+                };
+            }
+        }
+        Self::Chrome
+    }
+}
+
+/// User settings for the mouse pointer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PointerSettings {
+    pub text: CursorIcon,
+    pub chrome: CursorIcon,
+    pub vertical_separator: CursorIcon,
+    pub horizontal_separator: CursorIcon,
+    /// Whether to hide the pointer while typing until the mouse moves
+    pub hide_while_typing: bool,
+}
+
+impl PointerSettings {
+    pub fn icon(&self, region: Region) -> CursorIcon {
+        match region {
+            Region::Text => self.text,
+            Region::Chrome => self.chrome,
+            Region::VerticalSeparator => self.vertical_separator,
+            Region::HorizontalSeparator => self.horizontal_separator,
+        }
+    }
+}
+
+impl Default for PointerSettings {
+    fn default() -> Self {
+        Self {
+            text: CursorIcon::Text,
+            chrome: CursorIcon::Default,
+            vertical_separator: CursorIcon::ColResize,
+            horizontal_separator: CursorIcon::RowResize,
+            hide_while_typing: true,
+        }
+    }
+}
+
+impl Parse for PointerSettings {
+    fn parse(value: Value) -> Option<Self> {
+        let mut out = Self::default();
+        for (k, v) in parse_map(value)? {
+            match k.as_str()? {
+                "text" => out.text = parse_icon(v)?,
+                "chrome" => out.chrome = parse_icon(v)?,
+                "vertical_separator" => out.vertical_separator = parse_icon(v)?,
+                "horizontal_separator" => out.horizontal_separator = parse_icon(v)?,
+                "hide_while_typing" => out.hide_while_typing = v.maybe_into()?,
+                _ => {}
+            }
+        }
+        Some(out)
+    }
+}
+
+/// Parse a CSS cursor name, such as "text" or "col-resize"
+fn parse_icon(value: Value) -> Option<CursorIcon> {
+    CursorIcon::from_str(value.as_str()?).ok()
+}
@@ -1,23 +1,66 @@
-use crate::util::vec2::PixelVec;
-use std::path::PathBuf;
+use super::pointer::PointerSettings;
+use crate::{
+    rendering::{
+        animation::{EasingSettings, WindowAnimationSettings},
+        grids::{DimSettings, FloatStyle, WindowTransform},
+        layout::LayoutSettings,
+        pipelines::cursor_effects::CursorEffectSettings,
+    },
+    util::vec2::PixelVec,
+};
+use std::{collections::HashMap, path::PathBuf, time::Duration};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Settings {
     /// Multiplier of the default cursor speed
     pub cursor_speed: f32,
+    /// Trails and particles that follow the cursor
+    pub cursor_effects: CursorEffectSettings,
     /// Multiplier of the default scroll speed
     pub scroll_speed: f32,
+    /// Animation curves for scrolling and the cursors
+    pub easing: EasingSettings,
+    /// How floating windows move, appear, and disappear
+    pub window_animation: WindowAnimationSettings,
+    /// Offsets, opacities, and scales set by plugins, by window handle
+    pub window_transforms: HashMap<i64, WindowTransform>,
+    /// Rounded corners and shadows for floating windows
+    pub float_style: FloatStyle,
+    /// Styles for particular floating windows by window handle, in place of
+    /// float_style
+    pub window_styles: HashMap<i64, FloatStyle>,
+    /// Darkening of the windows the cursor isn't in
+    pub dim_inactive: DimSettings,
+    /// Whether trackpad scrolling moves the content by pixels rather than
+    /// whole lines
+    pub pixel_scroll: bool,
     /// Additional offset to apply to underlines
     pub underline_offset: i32,
+    /// Logical pixels to add between lines, from the linespace option
+    pub linespace: f32,
+    /// Logical pixels to add between letters
+    pub letter_spacing: f32,
+    /// Padding and alignment of the grid within the window
+    pub layout: LayoutSettings,
+    /// Whether to redraw continuously while there is a post-process shader,
+    /// for shaders that change over time
+    pub animate_post_process: bool,
     /// The size of the render surface, or None to use the default
     pub render_size: Option<PixelVec<u32>>,
     /// The directory to save frames to, or None if not rendering
     pub render_target: Option<PathBuf>,
     /// Overrides the background from Neovim's DefaultColorsSet event
     pub bg_override: Option<[f32; 4]>,
+    /// The longest time between clicks that counts as a multiclick
+    pub multiclick_interval: Duration,
+    pub pointer: PointerSettings,
     pub transparent: bool,
     pub raw_input: bool,
     pub send_frame_events: bool,
+    /// Whether dropped files are passed to a Lua handler instead of opened
+    pub drop_handler: bool,
+    /// Whether clicked links are passed to a Lua handler instead of opened
+    pub link_handler: bool,
 }
 
 impl Settings {
@@ -33,14 +76,30 @@ impl Default for Settings {
     fn default() -> Self {
         Self {
             cursor_speed: 1.,
+            cursor_effects: CursorEffectSettings::default(),
             scroll_speed: 1.,
+            easing: EasingSettings::default(),
+            window_animation: WindowAnimationSettings::default(),
+            window_transforms: HashMap::new(),
+            float_style: FloatStyle::default(),
+            window_styles: HashMap::new(),
+            dim_inactive: DimSettings::default(),
+            pixel_scroll: false,
             underline_offset: 0,
+            linespace: 0.,
+            letter_spacing: 0.,
+            layout: LayoutSettings::default(),
+            animate_post_process: false,
             render_size: None,
             render_target: None,
             bg_override: None,
+            multiclick_interval: Duration::from_millis(500),
+            pointer: PointerSettings::default(),
             transparent: false,
             raw_input: false,
             send_frame_events: false,
+            drop_handler: false,
+            link_handler: false,
         }
     }
 }
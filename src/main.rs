@@ -99,7 +99,7 @@ fn main() {
     });
 
     {
-        let mut handler = EventHandler::new(neovim, transparent);
+        let mut handler = EventHandler::new(neovim, transparent, event_loop.create_proxy());
         event_loop.set_control_flow(ControlFlow::Wait);
         event_loop
             .run_app(&mut handler)
@@ -137,5 +137,7 @@ fn main() {
 pub enum UserEvent {
     Notification(rpc::Notification),
     Request(rpc::Request),
+    /// The post-process shader file has changed
+    PostProcessChanged,
     Shutdown,
 }
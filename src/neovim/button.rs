@@ -6,6 +6,10 @@ pub enum Button {
     Left,
     Right,
     Middle,
+    /// The back button
+    X1,
+    /// The forward button
+    X2,
     Wheel,
     Move,
 }
@@ -16,6 +20,8 @@ impl From<Button> for &str {
             Button::Left => "left",
             Button::Right => "right",
             Button::Middle => "middle",
+            Button::X1 => "x1",
+            Button::X2 => "x2",
             Button::Wheel => "wheel",
             Button::Move => "move",
         }
@@ -37,9 +43,9 @@ impl TryFrom<MouseButton> for Button {
             MouseButton::Left => Ok(Self::Left),
             MouseButton::Right => Ok(Self::Right),
             MouseButton::Middle => Ok(Self::Middle),
-            MouseButton::Back | MouseButton::Forward | MouseButton::Other(_) => {
-                Err(ButtonFromWinitError)
-            }
+            MouseButton::Back => Ok(Self::X1),
+            MouseButton::Forward => Ok(Self::X2),
+            MouseButton::Other(_) => Err(ButtonFromWinitError),
         }
     }
 }
@@ -1,7 +1,7 @@
 pub mod action;
 pub mod button;
 mod incoming;
-mod modifiers;
+pub mod modifiers;
 mod stdin_thread;
 pub mod stdout_thread;
 
@@ -9,7 +9,10 @@ use self::{
     action::Action, button::Button, incoming::Incoming, modifiers::Modifiers,
     stdin_thread::StdinThread, stdout_thread::StdoutThread,
 };
-use crate::rpc::{self, Request};
+use crate::{
+    event::messagepack_ext_types::Window,
+    rpc::{self, Request},
+};
 use rmpv::Value;
 use std::{
     ffi::OsStr,
@@ -137,6 +140,10 @@ impl Neovim {
         self.call("nvim_ui_try_resize_grid", args);
     }
 
+    pub fn set_current_win(&mut self, win: Window) {
+        self.call("nvim_set_current_win", vec![win.into()]);
+    }
+
     pub fn ui_set_focus(&mut self, focus: bool) {
         self.call("nvim_ui_set_focus", vec![focus.into()]);
     }
@@ -10,6 +10,9 @@ impl Modifiers {
     const SHIFT: u8 = 0b0010;
     const ALT:   u8 = 0b0100;
     const LOGO:  u8 = 0b1000;
+    /// The number of clicks minus one, for multiclicks
+    const CLICKS: u8 = 0b11_0000;
+    const CLICKS_SHIFT: u32 = 4;
 }
 
 impl Modifiers {
@@ -48,6 +51,16 @@ impl Modifiers {
     pub fn logo(self) -> bool {
         self.0 & Self::LOGO > 0
     }
+
+    /// Set the number of consecutive clicks, from 1 to 4
+    pub fn with_clicks(self, clicks: u8) -> Self {
+        let clicks = clicks.clamp(1, 4) - 1;
+        Self((self.0 & !Self::CLICKS) | (clicks << Self::CLICKS_SHIFT))
+    }
+
+    pub fn clicks(self) -> u8 {
+        ((self.0 & Self::CLICKS) >> Self::CLICKS_SHIFT) + 1
+    }
 }
 
 impl From<Modifiers> for String {
@@ -55,7 +68,12 @@ impl From<Modifiers> for String {
         let ctrl = if mods.ctrl() { "C" } else { "" };
         let shift = if mods.shift() { "S" } else { "" };
         let alt = if mods.alt() { "A" } else { "" };
-        format!("{ctrl}{shift}{alt}")
+        let logo = if mods.logo() { "D" } else { "" };
+        let clicks = match mods.clicks() {
+            1 => String::new(),
+            clicks => clicks.to_string(),
+        };
+        format!("{clicks}{ctrl}{shift}{alt}{logo}")
     }
 }
 
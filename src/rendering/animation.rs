@@ -0,0 +1,161 @@
+//! Easing curves and settings for scroll, cursor, and window animations
+
+use crate::util::{parse_map, MaybeInto, Parse};
+use rmpv::Value;
+
+/// How an animation progresses toward its target
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Easing {
+    #[default]
+    EaseOutQuad,
+    Linear,
+    EaseOutCubic,
+    EaseOutExpo,
+    /// A critically damped spring, which settles without overshooting.
+    /// Stiffer springs settle sooner.
+    Spring {
+        stiffness: f32,
+    },
+}
+
+impl Easing {
+    pub const DEFAULT_STIFFNESS: f32 = 100.;
+
+    /// The remaining distance below which a spring is considered settled, in
+    /// the units of the animated distance
+    const SPRING_REST: f32 = 0.01;
+
+    /// The progress from 0 to 1 of an animation over the given distance after
+    /// the given time. Longer distances take longer, and time can be scaled
+    /// to change the speed. Returns exactly 1 when the animation is finished.
+    pub fn ease(self, t: f32, length: f32) -> f32 {
+        let duration = length.sqrt() + length.ln_1p();
+        if duration <= 0. {
+            return 1.;
+        }
+        let t = t / duration;
+        if let Self::Spring { stiffness } = self {
+            let omega = stiffness.max(0.).sqrt();
+            let remaining = (1. + omega * t) * (-omega * t).exp();
+            return if remaining * length < Self::SPRING_REST {
+                1.
+            } else {
+                1. - remaining
+            };
+        }
+
+        let t = t.clamp(0., 1.);
+        let v = 1. - t;
+        match self {
+            Self::EaseOutQuad => 1. - v * v,
+            Self::Linear => t,
+            Self::EaseOutCubic => 1. - v * v * v,
+            Self::EaseOutExpo if t >= 1. => 1.,
+            Self::EaseOutExpo => 1. - 2f32.powf(-10. * t),
+            Self::Spring { .. } => unreachable!(),
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "ease_out_quad" => Self::EaseOutQuad,
+            "linear" => Self::Linear,
+            "ease_out_cubic" => Self::EaseOutCubic,
+            "ease_out_expo" => Self::EaseOutExpo,
+            "spring" => Self::Spring {
+                stiffness: Self::DEFAULT_STIFFNESS,
+            },
+            _ => return None,
+        })
+    }
+}
+
+impl Parse for Easing {
+    fn parse(value: Value) -> Option<Self> {
+        if let Some(name) = value.as_str() {
+            return Self::from_name(name);
+        }
+
+        let mut kind = None;
+        let mut stiffness = None;
+        for (k, v) in parse_map(value)? {
+            match k.as_str()? {
+                "kind" => kind = Some(Self::from_name(v.as_str()?)?),
+                "stiffness" => stiffness = Some(v.maybe_into()?),
+                _ => {}
+            }
+        }
+        match (kind?, stiffness) {
+            (Self::Spring { .. }, Some(stiffness)) => Some(Self::Spring { stiffness }),
+            (kind, _) => Some(kind),
+        }
+    }
+}
+
+/// The easing for each kind of animation
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct EasingSettings {
+    pub scroll: Easing,
+    pub cursor: Easing,
+    pub cmdline_cursor: Easing,
+}
+
+impl Parse for EasingSettings {
+    fn parse(value: Value) -> Option<Self> {
+        let mut out = Self::default();
+        for (k, v) in parse_map(value)? {
+            match k.as_str()? {
+                "scroll" => out.scroll = v.maybe_into()?,
+                "cursor" => out.cursor = v.maybe_into()?,
+                "cmdline_cursor" => out.cmdline_cursor = v.maybe_into()?,
+                _ => {}
+            }
+        }
+        Some(out)
+    }
+}
+
+/// How floating windows appear or disappear
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WindowTransition {
+    #[default]
+    None,
+    Fade,
+    /// Fade while growing to full size, or shrinking when closing
+    Scale,
+}
+
+impl Parse for WindowTransition {
+    fn parse(value: Value) -> Option<Self> {
+        Some(match value.as_str()? {
+            "none" => Self::None,
+            "fade" => Self::Fade,
+            "scale" => Self::Scale,
+            _ => return None,
+        })
+    }
+}
+
+/// How floating windows are animated. Everything is off by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct WindowAnimationSettings {
+    /// Whether floating windows move smoothly to new positions
+    pub movement: bool,
+    pub open: WindowTransition,
+    pub close: WindowTransition,
+}
+
+impl Parse for WindowAnimationSettings {
+    fn parse(value: Value) -> Option<Self> {
+        let mut out = Self::default();
+        for (k, v) in parse_map(value)? {
+            match k.as_str()? {
+                "movement" => out.movement = v.maybe_into()?,
+                "open" => out.open = v.maybe_into()?,
+                "close" => out.close = v.maybe_into()?,
+                _ => {}
+            }
+        }
+        Some(out)
+    }
+}
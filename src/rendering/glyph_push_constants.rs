@@ -9,6 +9,8 @@ pub struct GlyphPushConstants {
     pub offset: PixelVec<i32>,
     pub z: f32,
     pub atlas_size: i32,
+    pub dim: f32,
+    pub padding: u32,
 }
 
 impl GlyphPushConstants {
@@ -1,20 +1,387 @@
-use super::{scrolling_grids::ScrollingGrids, text::Text};
+use super::{
+    animation::{Easing, WindowAnimationSettings, WindowTransition},
+    scrolling_grids::ScrollingGrids,
+    text::Text,
+    Motion,
+};
 use crate::{
     event::rgb::Rgb,
+    event_handler::settings::Settings,
     text::{cache::FontCache, fonts::Fonts},
-    ui::{self, Ui},
+    ui::{self, window::Window, Ui},
+    util::{
+        parse_map,
+        vec2::{CellVec, PixelVec, Vec2},
+        MaybeInto, Parse,
+    },
 };
-use std::collections::HashMap;
+use rmpv::Value;
+use std::{collections::HashMap, time::Duration};
 use swash::shape::ShapeContext;
 
+/// Adjustments from plugins to how a window is drawn
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindowTransform {
+    /// Pixels to move the window by on top of its position from Neovim
+    pub offset: PixelVec<f32>,
+    pub opacity: f32,
+    /// The size to draw the window at relative to its actual size, scaling
+    /// from its center
+    pub scale: f32,
+}
+
+impl Default for WindowTransform {
+    fn default() -> Self {
+        Self {
+            offset: PixelVec::new(0., 0.),
+            opacity: 1.,
+            scale: 1.,
+        }
+    }
+}
+
+/// How floating windows are framed
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FloatStyle {
+    /// The radius of the rounded corners in pixels
+    pub corner_radius: f32,
+    pub shadow: Option<Shadow>,
+}
+
+impl FloatStyle {
+    /// Whether the style changes how a window is drawn
+    fn is_visible(&self) -> bool {
+        self.corner_radius > 0. || self.shadow.is_some()
+    }
+}
+
+impl Parse for FloatStyle {
+    fn parse(value: Value) -> Option<Self> {
+        let mut out = Self::default();
+        for (k, v) in parse_map(value)? {
+            match k.as_str()? {
+                "corner_radius" => out.corner_radius = f32::parse(v)?.max(0.),
+                "shadow" => {
+                    out.shadow = match v {
+                        Value::Boolean(false) | Value::Nil => None,
+                        Value::Boolean(true) => Some(Shadow::default()),
+                        v => Some(v.maybe_into()?),
+                    }
+                }
+                _ => {}
+            }
+        }
+        Some(out)
+    }
+}
+
+/// A soft shadow drawn beneath a floating window
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Shadow {
+    /// How far the shadow fades out past its edges in pixels
+    pub blur: f32,
+    /// Pixels to move the shadow by relative to the window
+    pub offset: PixelVec<f32>,
+    pub color: Rgb,
+    pub opacity: f32,
+}
+
+impl Default for Shadow {
+    fn default() -> Self {
+        Self {
+            blur: 16.,
+            offset: PixelVec::new(0., 4.),
+            color: Rgb::BLACK,
+            opacity: 0.5,
+        }
+    }
+}
+
+impl Parse for Shadow {
+    fn parse(value: Value) -> Option<Self> {
+        let mut out = Self::default();
+        for (k, v) in parse_map(value)? {
+            match k.as_str()? {
+                "blur" => out.blur = f32::parse(v)?.max(1.),
+                "x" => out.offset.0.x = v.maybe_into()?,
+                "y" => out.offset.0.y = v.maybe_into()?,
+                "color" => out.color = v.maybe_into()?,
+                "opacity" => out.opacity = f32::parse(v)?.clamp(0., 1.),
+                _ => {}
+            }
+        }
+        Some(out)
+    }
+}
+
+/// Darkening of the windows the cursor isn't in, as a focus aid
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DimSettings {
+    pub enabled: bool,
+    /// How much to darken and desaturate inactive windows, from 0 to 1
+    pub amount: f32,
+}
+
+impl Default for DimSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            amount: 0.3,
+        }
+    }
+}
+
+impl Parse for DimSettings {
+    fn parse(value: Value) -> Option<Self> {
+        let mut out = Self::default();
+        for (k, v) in parse_map(value)? {
+            match k.as_str()? {
+                "enabled" => out.enabled = v.maybe_into()?,
+                "amount" => out.amount = f32::parse(v)?.clamp(0., 1.),
+                _ => {}
+            }
+        }
+        Some(out)
+    }
+}
+
 pub struct Grid {
     pub text: Text,
     pub scrolling: ScrollingGrids,
+    /// The handle number of the Neovim window the grid belongs to
+    window: Option<i64>,
+    /// Whether the grid belongs to a floating window. Only floating windows
+    /// are animated.
+    floating: bool,
+    /// The window position from the last update
+    target: Option<CellVec<f32>>,
+    /// The window position at this point in the animation
+    position: Option<CellVec<f32>>,
+    movement: Option<Movement>,
+    transition: Option<Transition>,
+    transform: WindowTransform,
+    style: FloatStyle,
+    /// Whether the grid belongs to a normal window the cursor isn't in
+    inactive: bool,
+    /// How far the grid is in dimming, from 0 to 1
+    dim: f32,
 }
 
 impl Grid {
     pub fn new(text: Text, scrolling: ScrollingGrids) -> Self {
-        Self { text, scrolling }
+        Self {
+            text,
+            scrolling,
+            window: None,
+            floating: false,
+            target: None,
+            position: None,
+            movement: None,
+            transition: None,
+            transform: WindowTransform::default(),
+            style: FloatStyle::default(),
+            inactive: false,
+            dim: 0.,
+        }
+    }
+
+    /// The rounded corners and shadow of the grid's floating window
+    pub fn style(&self) -> FloatStyle {
+        self.style
+    }
+
+    /// The opacity to draw the grid with
+    pub fn opacity(&self) -> f32 {
+        self.visibility() * self.transform.opacity
+    }
+
+    /// The size to draw the grid at relative to its actual size, scaling from
+    /// its center
+    pub fn scale(&self) -> f32 {
+        self.transition.as_ref().map_or(1., Transition::scale) * self.transform.scale
+    }
+
+    /// How far along the window is in appearing, from 0 to 1
+    fn visibility(&self) -> f32 {
+        self.transition
+            .as_ref()
+            .map_or(1., |transition| transition.visibility())
+    }
+
+    /// Whether the grid has to be drawn on its own and composited onto the
+    /// frame rather than drawn along with the other grids. This includes
+    /// floating windows with winblend or pumblend so that the grids below show
+    /// through them.
+    pub fn is_layered(&self) -> bool {
+        self.opacity() < 1.
+            || self.scale() != 1.
+            || self.style.is_visible()
+            || (self.floating && self.text.is_translucent())
+    }
+
+    fn is_closing(&self) -> bool {
+        self.transition
+            .as_ref()
+            .is_some_and(|transition| transition.closing)
+    }
+
+    /// Move the window to the given position, either smoothly or at once
+    fn move_to(&mut self, target: Option<CellVec<f32>>, animate: bool) {
+        if target == self.target {
+            return;
+        }
+        self.target = target;
+        match (self.position, target) {
+            (Some(start), Some(_)) if animate => {
+                self.movement = Some(Movement {
+                    start,
+                    elapsed: Duration::ZERO,
+                });
+            }
+            _ => {
+                self.movement = None;
+                self.position = target;
+            }
+        }
+    }
+
+    /// Start the transition for a window being shown
+    fn open(&mut self, kind: WindowTransition) {
+        if !self.floating || kind == WindowTransition::None {
+            self.transition = None;
+            return;
+        }
+        // Pick up from a closing transition that was interrupted
+        let from = if self.is_closing() {
+            self.visibility()
+        } else {
+            0.
+        };
+        self.transition = Some(Transition::new(kind, from, false));
+    }
+
+    /// Start the transition for a window being hidden
+    fn close(&mut self, kind: WindowTransition) {
+        if !self.floating || kind == WindowTransition::None {
+            return;
+        }
+        self.transition = Some(Transition::new(kind, self.visibility(), true));
+    }
+
+    /// How long it takes a window to dim or brighten
+    const DIM_DURATION: Duration = Duration::from_millis(200);
+
+    fn advance(
+        &mut self,
+        delta_time: Duration,
+        speed: f32,
+        easing: Easing,
+        dim: DimSettings,
+        cell_size: Vec2<f32>,
+    ) -> Motion {
+        let mut motion = Motion::Still;
+
+        let dim_target = if dim.enabled && self.inactive { 1. } else { 0. };
+        if self.dim != dim_target {
+            let step = delta_time.as_secs_f32() / Self::DIM_DURATION.as_secs_f32();
+            self.dim = if dim_target > self.dim {
+                (self.dim + step).min(dim_target)
+            } else {
+                (self.dim - step).max(dim_target)
+            };
+            if self.dim != dim_target {
+                motion = Motion::Animating;
+            }
+        }
+        self.text.update_dim(self.dim * dim.amount);
+
+        if let Some(transition) = &mut self.transition {
+            transition.elapsed += delta_time;
+            if transition.is_finished() {
+                self.transition = None;
+            } else {
+                motion = Motion::Animating;
+            }
+        }
+
+        if let (Some(movement), Some(target)) = (&mut self.movement, self.target) {
+            movement.elapsed += delta_time;
+            let length = (target - movement.start).into_pixels(cell_size).length();
+            let t = if length < 0.25 {
+                1.
+            } else {
+                easing
+                    .ease(movement.elapsed.as_secs_f32() * speed, length)
+                    .min(1.)
+            };
+            self.position = Some(movement.start.lerp(target, t));
+            if t >= 1. {
+                self.movement = None;
+            } else {
+                motion = Motion::Animating;
+            }
+        }
+
+        let offset = self.transform.offset.into_cells(cell_size);
+        self.text
+            .update_window(self.position.map(|position| position + offset));
+
+        motion
+    }
+}
+
+/// A window moving from one position to another
+#[derive(Debug, Clone, Copy)]
+struct Movement {
+    start: CellVec<f32>,
+    elapsed: Duration,
+}
+
+/// A window fading in or out
+#[derive(Debug, Clone, Copy)]
+struct Transition {
+    kind: WindowTransition,
+    /// The visibility from 0 to 1 at the start of the transition
+    from: f32,
+    closing: bool,
+    elapsed: Duration,
+}
+
+impl Transition {
+    const DURATION: Duration = Duration::from_millis(150);
+    /// The scale of a window when it is not visible
+    const MIN_SCALE: f32 = 0.9;
+
+    fn new(kind: WindowTransition, from: f32, closing: bool) -> Self {
+        Self {
+            kind,
+            from,
+            closing,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    fn progress(&self) -> f32 {
+        (self.elapsed.as_secs_f32() / Self::DURATION.as_secs_f32()).min(1.)
+    }
+
+    fn is_finished(&self) -> bool {
+        self.progress() >= 1.
+    }
+
+    /// How visible the window is from 0 to 1, which is also its opacity
+    fn visibility(&self) -> f32 {
+        let v = 1. - self.progress();
+        let t = 1. - v * v;
+        let to = if self.closing { 0. } else { 1. };
+        self.from + (to - self.from) * t
+    }
+
+    fn scale(&self) -> f32 {
+        match self.kind {
+            WindowTransition::Scale => Self::MIN_SCALE + (1. - Self::MIN_SCALE) * self.visibility(),
+            WindowTransition::None | WindowTransition::Fade => 1.,
+        }
     }
 }
 
@@ -22,6 +389,8 @@ pub struct Grids {
     grids: HashMap<ui::grid::Id, Grid>,
     draw_order: Vec<ui::grid::Id>,
     bind_group_layout: wgpu::BindGroupLayout,
+    /// The animation settings from the last frame
+    animation: WindowAnimationSettings,
 }
 
 impl Grids {
@@ -42,6 +411,7 @@ impl Grids {
                     count: None,
                 }],
             }),
+            animation: WindowAnimationSettings::default(),
         }
     }
 
@@ -55,7 +425,20 @@ impl Grids {
         font_cache: &mut FontCache,
         shape_context: &mut ShapeContext,
     ) {
-        self.grids.retain(|id, _| ui.grid(*id).is_some());
+        // Windows that are no longer shown fade out before they are dropped
+        let previous_order = std::mem::take(&mut self.draw_order);
+        let is_shown = |id: ui::grid::Id| ui.draw_order.iter().any(|item| item.grid == id);
+        for &id in previous_order.iter() {
+            if !is_shown(id) {
+                if let Some(grid) = self.grids.get_mut(&id) {
+                    if !grid.is_closing() {
+                        grid.close(self.animation.close);
+                    }
+                }
+            }
+        }
+        self.grids
+            .retain(|id, grid| ui.grid(*id).is_some() || grid.is_closing());
 
         let fg = ui.default_colors.rgb_fg.unwrap_or(Rgb::WHITE);
         let bg = ui.default_colors.rgb_bg.unwrap_or(Rgb::BLACK);
@@ -70,7 +453,13 @@ impl Grids {
                 )
             });
 
-            let window_position = ui.position(ui_grid.id);
+            grid.window = ui
+                .window_handles
+                .get(&ui_grid.id)
+                .and_then(|window| window.number());
+            grid.floating = matches!(ui_grid.window(), Window::Floating(_));
+            grid.inactive =
+                matches!(ui_grid.window(), Window::Normal(_)) && ui_grid.id != ui.cursor.grid;
             if ui_grid.dirty.contents() {
                 if ui_grid.scroll_delta != 0 {
                     grid.scrolling
@@ -94,12 +483,74 @@ impl Grids {
                 );
             }
 
-            grid.text.update_window(window_position);
+            if is_shown(ui_grid.id) {
+                let was_shown = previous_order.contains(&ui_grid.id) && !grid.is_closing();
+                if !was_shown {
+                    grid.open(self.animation.open);
+                }
+                let animate = was_shown && grid.floating && self.animation.movement;
+                grid.move_to(ui.position(ui_grid.id), animate);
+            }
         }
 
-        self.draw_order.clear();
         self.draw_order
             .extend(ui.draw_order.iter().map(|draw_item| draw_item.grid));
+        // Keep closing windows above whatever they were drawn above
+        for (i, &id) in previous_order.iter().enumerate() {
+            if !self.grids.get(&id).is_some_and(Grid::is_closing) {
+                continue;
+            }
+            let position = previous_order[..i]
+                .iter()
+                .rev()
+                .find_map(|below| self.draw_order.iter().position(|id| id == below))
+                .map_or(0, |i| i + 1);
+            self.draw_order.insert(position, id);
+        }
+    }
+
+    /// Advance window animations
+    pub fn advance(
+        &mut self,
+        delta_time: Duration,
+        speed: f32,
+        easing: Easing,
+        settings: &Settings,
+        cell_size: Vec2<f32>,
+    ) -> Motion {
+        self.animation = settings.window_animation;
+        let mut motion = Motion::Still;
+        let mut closed = vec![];
+        for (&id, grid) in self.grids.iter_mut() {
+            grid.transform = grid
+                .window
+                .and_then(|window| settings.window_transforms.get(&window))
+                .copied()
+                .unwrap_or_default();
+            grid.style = if grid.floating {
+                grid.window
+                    .and_then(|window| settings.window_styles.get(&window))
+                    .copied()
+                    .unwrap_or(settings.float_style)
+            } else {
+                FloatStyle::default()
+            };
+            let was_closing = grid.is_closing();
+            motion = motion.soonest(grid.advance(
+                delta_time,
+                speed,
+                easing,
+                settings.dim_inactive,
+                cell_size,
+            ));
+            if was_closing && !grid.is_closing() {
+                closed.push(id);
+            }
+        }
+        // Closed windows stop being drawn. Their grids are dropped on the next
+        // update if Neovim has destroyed them.
+        self.draw_order.retain(|id| !closed.contains(id));
+        motion
     }
 
     pub fn bind_group_layout(&self) -> &wgpu::BindGroupLayout {
@@ -121,4 +572,18 @@ impl Grids {
     pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Grid> {
         self.grids.values_mut()
     }
+
+    pub fn get_mut(&mut self, id: ui::grid::Id) -> Option<&mut Grid> {
+        self.grids.get_mut(&id)
+    }
+
+    /// Take the lines each grid needs Neovim to scroll to keep up with
+    /// trackpad scrolling
+    pub fn scroll_requests(&mut self) -> Vec<(ui::grid::Id, i32)> {
+        self.grids
+            .iter_mut()
+            .map(|(&id, grid)| (id, grid.scrolling.request_lines()))
+            .filter(|&(_, lines)| lines != 0)
+            .collect()
+    }
 }
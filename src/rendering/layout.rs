@@ -0,0 +1,197 @@
+//! Placement of the grid within the window. Rendering and hit-testing both go
+//! by this so that they agree on where cells are.
+
+use crate::{
+    event::rgb::Rgb,
+    util::{
+        parse_map,
+        vec2::{CellVec, PixelVec, Vec2},
+        MaybeInto, Parse,
+    },
+};
+use rmpv::Value;
+
+/// Space to leave around the grid, in pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Padding {
+    pub top: u32,
+    pub right: u32,
+    pub bottom: u32,
+    pub left: u32,
+}
+
+impl Parse for Padding {
+    fn parse(value: Value) -> Option<Self> {
+        // A single number pads every side by the same amount
+        if let Some(all) = value.as_u64() {
+            let all = all.try_into().ok()?;
+            return Some(Self {
+                top: all,
+                right: all,
+                bottom: all,
+                left: all,
+            });
+        }
+
+        let mut out = Self::default();
+        for (k, v) in parse_map(value)? {
+            match k.as_str()? {
+                "top" => out.top = v.maybe_into()?,
+                "right" => out.right = v.maybe_into()?,
+                "bottom" => out.bottom = v.maybe_into()?,
+                "left" => out.left = v.maybe_into()?,
+                _ => {}
+            }
+        }
+        Some(out)
+    }
+}
+
+/// Where the grid goes in the space left over when the window is not a whole
+/// number of cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Alignment {
+    #[default]
+    Center,
+    TopLeft,
+}
+
+impl Parse for Alignment {
+    fn parse(value: Value) -> Option<Self> {
+        Some(match value.as_str()? {
+            "center" => Self::Center,
+            "top_left" => Self::TopLeft,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LayoutSettings {
+    pub padding: Padding,
+    pub alignment: Alignment,
+    /// The color of the space around the grid, or None for the default
+    /// background color
+    pub fill: Option<Rgb>,
+}
+
+impl Parse for LayoutSettings {
+    fn parse(value: Value) -> Option<Self> {
+        let mut out = Self::default();
+        for (k, v) in parse_map(value)? {
+            match k.as_str()? {
+                "padding" => out.padding = v.maybe_into()?,
+                "alignment" => out.alignment = v.maybe_into()?,
+                "fill" => {
+                    out.fill = match v {
+                        Value::Nil => None,
+                        v => Some(v.maybe_into()?),
+                    }
+                }
+                _ => {}
+            }
+        }
+        Some(out)
+    }
+}
+
+/// The area of the window the grid is drawn in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Layout {
+    /// The top-left corner of the grid relative to the window
+    pub offset: PixelVec<u32>,
+    /// The size of the grid, a whole number of cells
+    pub size: PixelVec<u32>,
+}
+
+impl Layout {
+    pub fn new(window_size: PixelVec<u32>, cell_size: Vec2<u32>, settings: LayoutSettings) -> Self {
+        let Padding {
+            top,
+            right,
+            bottom,
+            left,
+        } = settings.padding;
+        let inner = PixelVec::new(
+            window_size.0.x.saturating_sub(left + right),
+            window_size.0.y.saturating_sub(top + bottom),
+        );
+        // Keep at least one cell so there is always something to draw to
+        let size = inner
+            .into_cells(cell_size)
+            .map(|cells| cells.max(1))
+            .into_pixels(cell_size);
+        let leftover = match settings.alignment {
+            Alignment::Center => PixelVec::combine(inner, size, u32::saturating_sub) / 2,
+            Alignment::TopLeft => PixelVec::new(0, 0),
+        };
+        Self {
+            offset: PixelVec::new(left, top) + leftover,
+            size,
+        }
+    }
+
+    /// The size of the grid in cells
+    pub fn cells(&self, cell_size: Vec2<u32>) -> CellVec<u32> {
+        self.size.into_cells(cell_size)
+    }
+
+    /// Convert a position in the window to a position relative to the grid,
+    /// or None if it is above or left of the grid
+    pub fn to_grid(self, position: PixelVec<i64>) -> Option<PixelVec<u32>> {
+        (position - self.offset.cast()).try_cast().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CELL: Vec2<u32> = Vec2 { x: 10, y: 20 };
+
+    #[test]
+    fn centers_leftover_space() {
+        let layout = Layout::new(PixelVec::new(105, 210), CELL, LayoutSettings::default());
+        assert_eq!(layout.size, PixelVec::new(100, 200));
+        assert_eq!(layout.offset, PixelVec::new(2, 5));
+        assert_eq!(layout.cells(CELL), CellVec::new(10, 10));
+    }
+
+    #[test]
+    fn padding_and_top_left() {
+        let settings = LayoutSettings {
+            padding: Padding {
+                top: 4,
+                right: 6,
+                bottom: 0,
+                left: 8,
+            },
+            alignment: Alignment::TopLeft,
+            fill: None,
+        };
+        let layout = Layout::new(PixelVec::new(105, 210), CELL, settings);
+        assert_eq!(layout.size, PixelVec::new(90, 200));
+        assert_eq!(layout.offset, PixelVec::new(8, 4));
+        assert_eq!(
+            layout.to_grid(PixelVec::new(18, 4)),
+            Some(PixelVec::new(10, 0))
+        );
+        assert_eq!(layout.to_grid(PixelVec::new(7, 30)), None);
+    }
+
+    #[test]
+    fn padding_larger_than_window() {
+        let settings = LayoutSettings {
+            padding: Padding {
+                top: 300,
+                right: 0,
+                bottom: 0,
+                left: 0,
+            },
+            ..Default::default()
+        };
+        let layout = Layout::new(PixelVec::new(105, 210), CELL, settings);
+        assert_eq!(layout.cells(CELL), CellVec::new(10, 1));
+        assert_eq!(layout.offset, PixelVec::new(2, 300));
+    }
+}
@@ -1,7 +1,9 @@
+pub mod animation;
 mod cmdline_grid;
 mod glyph_bind_group;
 mod glyph_push_constants;
-mod grids;
+pub mod grids;
+pub mod layout;
 mod message_grids;
 pub mod pipelines;
 mod scrolling_grids;
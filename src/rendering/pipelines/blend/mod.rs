@@ -1,7 +1,8 @@
 //! Alpha-blends one texture over another. This is used to combine the results
 //! of the monochrome and lines pipelines with the background cells. These need
 //! to rendered separately so that the alphas of the monochrome and lines can be
-//! used for rendering the cursor.
+//! used for rendering the cursor. The same goes for the monochrome target of
+//! grids drawn on their own layer.
 
 use crate::rendering::{nearest_sampler, texture::Texture};
 use wgpu::include_wgsl;
@@ -11,12 +12,17 @@ pub struct Pipeline {
     pipeline: wgpu::RenderPipeline,
     bind_group_layout: wgpu::BindGroupLayout,
     bind_group: wgpu::BindGroup,
+    layer_bind_group: wgpu::BindGroup,
     sampler: wgpu::Sampler,
     shader: wgpu::ShaderModule,
 }
 
 impl Pipeline {
-    pub fn new(device: &wgpu::Device, texture_view: &wgpu::TextureView) -> Self {
+    pub fn new(
+        device: &wgpu::Device,
+        texture_view: &wgpu::TextureView,
+        layer_texture_view: &wgpu::TextureView,
+    ) -> Self {
         let sampler = nearest_sampler(device);
         let shader = device.create_shader_module(include_wgsl!("blend.wgsl"));
 
@@ -51,6 +57,7 @@ impl Pipeline {
         Self {
             pipeline: pipeline(device, &pipeline_layout, &shader),
             bind_group: bind_group(device, &bind_group_layout, texture_view, &sampler),
+            layer_bind_group: bind_group(device, &bind_group_layout, layer_texture_view, &sampler),
             pipeline_layout,
             bind_group_layout,
             sampler,
@@ -58,12 +65,41 @@ impl Pipeline {
         }
     }
 
-    pub fn update(&mut self, device: &wgpu::Device, texture_view: &wgpu::TextureView) {
+    pub fn update(
+        &mut self,
+        device: &wgpu::Device,
+        texture_view: &wgpu::TextureView,
+        layer_texture_view: &wgpu::TextureView,
+    ) {
         self.pipeline = pipeline(device, &self.pipeline_layout, &self.shader);
         self.bind_group = bind_group(device, &self.bind_group_layout, texture_view, &self.sampler);
+        self.layer_bind_group = bind_group(
+            device,
+            &self.bind_group_layout,
+            layer_texture_view,
+            &self.sampler,
+        );
     }
 
     pub fn render(&self, encoder: &mut wgpu::CommandEncoder, color_target: &wgpu::TextureView) {
+        self.render_bind_group(encoder, color_target, &self.bind_group);
+    }
+
+    /// Blend the layer monochrome target
+    pub fn render_layer(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        color_target: &wgpu::TextureView,
+    ) {
+        self.render_bind_group(encoder, color_target, &self.layer_bind_group);
+    }
+
+    fn render_bind_group(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        color_target: &wgpu::TextureView,
+        bind_group: &wgpu::BindGroup,
+    ) {
         let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
             label: Some("Blend render pass"),
             color_attachments: &[Some(wgpu::RenderPassColorAttachment {
@@ -80,7 +116,7 @@ impl Pipeline {
         });
 
         render_pass.set_pipeline(&self.pipeline);
-        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        render_pass.set_bind_group(0, bind_group, &[]);
         render_pass.draw(0..6, 0..1);
     }
 }
@@ -12,6 +12,7 @@ struct GridInfo {
     cell_size: vec2<i32>,
     offset: vec2<i32>,
     z: f32,
+    dim: f32,
 }
 
 @group(0) @binding(0)
@@ -39,7 +40,10 @@ fn vs_main(
     );
 
     var out: VertexOutput;
-    out.color = vec4<f32>(grid_cell.r, grid_cell.g, grid_cell.b, grid_cell.a);
+    out.color = vec4<f32>(
+        dim(vec3<f32>(grid_cell.r, grid_cell.g, grid_cell.b), grid_info.dim),
+        grid_cell.a,
+    );
     out.clip_position = vec4<f32>(
         vec2<f32>(((pos + vec2<i32>(tex_coord)) * vec2<i32>(grid_info.cell_size)) + grid_info.offset) / 
         vec2<f32>(grid_info.target_size) * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0),
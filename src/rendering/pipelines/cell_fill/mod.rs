@@ -2,6 +2,7 @@
 //! fill color. The default fill is already written by the default_fill
 //! pipeline.
 
+use super::with_dim;
 use crate::{
     rendering::{
         text::{set_scissor, Text},
@@ -10,7 +11,6 @@ use crate::{
     util::vec2::{PixelVec, Vec2},
 };
 use bytemuck::{checked::cast_slice, Pod, Zeroable};
-use wgpu::include_wgsl;
 
 pub struct Pipeline {
     pipeline: wgpu::RenderPipeline,
@@ -22,7 +22,8 @@ impl Pipeline {
         grid_bind_group_layout: &wgpu::BindGroupLayout,
         texture_format: wgpu::TextureFormat,
     ) -> Self {
-        let shader = device.create_shader_module(include_wgsl!("cell_fill.wgsl"));
+        let shader =
+            device.create_shader_module(with_dim("cell_fill.wgsl", include_str!("cell_fill.wgsl")));
 
         let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: Some("Cell fill pipeline layout"),
@@ -128,7 +129,7 @@ impl Pipeline {
                     cell_size: cell_size.try_cast().unwrap(),
                     offset: offset + scroll_offset,
                     z,
-                    padding: 0,
+                    dim: grid.dim(),
                 }
                 .set(&mut render_pass);
                 render_pass.draw(0..grid.cell_fill_count() * 6, 0..1);
@@ -144,7 +145,7 @@ pub struct PushConstants {
     pub cell_size: Vec2<i32>,
     pub offset: PixelVec<i32>,
     pub z: f32,
-    pub padding: u32,
+    pub dim: f32,
 }
 
 impl PushConstants {
@@ -7,12 +7,11 @@ use crate::{
         mode_info_set::{CursorShape, ModeInfo},
         rgb::Rgb,
     },
-    rendering::{nearest_sampler, texture::Texture, Motion},
-    ui::{cmdline::Mode, Ui},
+    rendering::{animation::Easing, nearest_sampler, texture::Texture, Motion},
+    ui::Ui,
     util::{
         mat3::Mat3,
-        nice_s_curve,
-        vec2::{CellVec, PixelVec, Vec2},
+        vec2::{PixelVec, Vec2},
     },
 };
 use bytemuck::{cast_slice, Pod, Zeroable};
@@ -27,6 +26,7 @@ pub struct Pipeline {
     fragment_push_constants: FragmentPushConstants,
     display_info: Option<DisplayInfo>,
     speed: f32,
+    easing: Easing,
     transform: Mat3,
     show: bool,
 }
@@ -124,6 +124,7 @@ impl Pipeline {
             transform: Mat3::IDENTITY,
             show: false,
             speed: 0.,
+            easing: Easing::default(),
         }
     }
 
@@ -186,45 +187,29 @@ impl Pipeline {
                 }
             }
 
-            CursorKind::Cmdline => ui.cmdline.mode.as_ref().map(|mode| match mode {
-                Mode::Normal { levels } => {
-                    // We guarantee at least one level if the mode is Some
-                    let level = levels.last().unwrap();
-                    let mut pos =
-                        CellVec::new(level.cursor_pos as i64, -(level.content_lines.len() as i64));
-                    for line in level.content_lines.iter() {
-                        pos.0.y += 1;
-                        let line_len = line
-                            .chunks
-                            .iter()
-                            .fold(0, |acc, chunk| acc + chunk.text_chunk.len());
-                        if line_len < pos.0.x as usize {
-                            pos.0.x -= line_len as i64;
-                        } else {
-                            break;
-                        }
-                    }
-                    pos.0.x += level.prompt.len() as i64 + 1;
-                    let base = CellVec::new(0, ui.grids[0].contents().size.0.y - 1);
-                    pos.cast_as::<f32>() + base.cast_as()
-                }
-
-                Mode::Block {
-                    previous_lines: _,
-                    current_line: _,
-                } => todo!(),
+            CursorKind::Cmdline => ui.cmdline.layout().map(|layout| {
+                let base_grid_height = ui.grids[0].contents().size.0.y;
+                layout.cursor_position(base_grid_height).cast_as::<f32>()
             }),
         };
 
         let position = position.map(|pos| pos.cast_as::<f32>());
 
+        // Cover both cells of a double-width character
+        let is_wide = kind == CursorKind::Normal
+            && ui
+                .grid(ui.cursor.grid)
+                .is_some_and(|grid| grid.contents().is_wide(ui.cursor.pos));
+        let width = if is_wide { 2. } else { 1. };
+
         let fill = mode.cell_percentage.unwrap_or(10) as f32 / 100.0;
         let fill = match mode.cursor_shape.unwrap_or(CursorShape::Block) {
             CursorShape::Block => Vec2::new(1.0, 1.0),
             CursorShape::Horizontal => Vec2::new(1.0, fill),
-            CursorShape::Vertical => Vec2::new(fill, 1.0),
+            // Keep the bar as thick as it is over a single cell
+            CursorShape::Vertical => Vec2::new(fill / width, 1.0),
         };
-        let cursor_size = cell_size - 1.;
+        let cursor_size = cell_size * Vec2::new(width, 1.) - 1.;
 
         self.display_info = match (position, self.display_info.as_ref()) {
             (None, None) | (None, Some(_)) => None,
@@ -242,9 +227,10 @@ impl Pipeline {
             (Some(position), Some(display_info)) => {
                 let new_target = position.into_pixels(cell_size);
                 let (start_position, elapsed) = if new_target != display_info.target_position {
-                    let current_position = display_info
-                        .start_position
-                        .lerp(display_info.target_position, t(display_info, self.speed));
+                    let current_position = display_info.start_position.lerp(
+                        display_info.target_position,
+                        t(display_info, self.speed, self.easing),
+                    );
                     (current_position, Duration::ZERO)
                 } else {
                     (display_info.start_position, display_info.elapsed)
@@ -263,14 +249,21 @@ impl Pipeline {
         };
     }
 
-    pub fn advance(&mut self, delta_time: Duration, speed: f32, cell_size: Vec2<f32>) -> Motion {
+    pub fn advance(
+        &mut self,
+        delta_time: Duration,
+        speed: f32,
+        easing: Easing,
+        cell_size: Vec2<f32>,
+    ) -> Motion {
         self.speed = speed;
+        self.easing = easing;
         let Some(display_info) = self.display_info.as_mut() else {
             return Motion::Still;
         };
 
         display_info.elapsed += delta_time;
-        let t = t(display_info, speed).min(1.);
+        let t = t(display_info, speed, easing).min(1.);
         let current_position = display_info
             .start_position
             .lerp(display_info.target_position, t);
@@ -334,6 +327,31 @@ impl Pipeline {
         motion
     }
 
+    /// The top-left corner and size of the area covered by the cursor at its
+    /// current point in the animation, regardless of blinking
+    pub fn rect(&self) -> Option<(PixelVec<f32>, Vec2<f32>)> {
+        let display_info = self.display_info.as_ref()?;
+        let t = t(display_info, self.speed, self.easing).min(1.);
+        let position = display_info
+            .start_position
+            .lerp(display_info.target_position, t);
+        let size = display_info.cursor_size * display_info.fill;
+        let offset = Vec2::new(0., display_info.cursor_size.y - size.y);
+        Some((position + PixelVec(offset), size))
+    }
+
+    /// Where the cursor is moving to
+    pub fn target(&self) -> Option<PixelVec<f32>> {
+        self.display_info
+            .as_ref()
+            .map(|display_info| display_info.target_position)
+    }
+
+    /// The linear color of the cursor
+    pub fn color(&self) -> [f32; 4] {
+        self.fragment_push_constants.fg
+    }
+
     pub fn render(
         &mut self,
         encoder: &mut wgpu::CommandEncoder,
@@ -443,17 +461,18 @@ impl VertexPushConstants {
     pub const SIZE: u32 = size_of::<Self>() as u32;
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CursorKind {
     Normal,
     Cmdline,
 }
 
-fn t(display_info: &DisplayInfo, speed: f32) -> f32 {
+fn t(display_info: &DisplayInfo, speed: f32, easing: Easing) -> f32 {
     let length = (display_info.target_position - display_info.start_position).length();
     if length < 0.25 {
         1.0
     } else {
-        nice_s_curve(display_info.elapsed.as_secs_f32() * speed, length)
+        easing.ease(display_info.elapsed.as_secs_f32() * speed, length)
     }
 }
 
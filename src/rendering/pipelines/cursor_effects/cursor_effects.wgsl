@@ -0,0 +1,54 @@
+struct PushConstants {
+    target_size: vec2<f32>,
+}
+
+struct Sprite {
+    position: vec2<f32>,
+    size: vec2<f32>,
+    color: vec4<f32>,
+    roundness: f32,
+}
+
+@group(0) @binding(0)
+var<storage, read> sprites: array<Sprite>;
+var<push_constant> push_constants: PushConstants;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+    @location(1) uv: vec2<f32>,
+    @location(2) roundness: f32,
+}
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) in_vertex_index: u32,
+) -> VertexOutput {
+    let sprite = sprites[in_vertex_index / 6u];
+    let uv = vec2<f32>(
+        f32(in_vertex_index % 2u),
+        f32(((in_vertex_index + 5u) % 6u) / 3u),
+    );
+    let position = sprite.position + uv * sprite.size;
+
+    var out: VertexOutput;
+    out.color = sprite.color;
+    out.uv = uv;
+    out.roundness = sprite.roundness;
+    out.clip_position = vec4<f32>(
+        position / push_constants.target_size * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0),
+        0.0,
+        1.0,
+    );
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Distance from the center, reaching 1 at the edges
+    let distance = length(in.uv * 2.0 - 1.0);
+    let edge = fwidth(distance);
+    let circle = 1.0 - smoothstep(1.0 - edge, 1.0, distance);
+    let alpha = mix(1.0, circle, in.roundness);
+    return vec4<f32>(in.color.rgb, in.color.a * alpha);
+}
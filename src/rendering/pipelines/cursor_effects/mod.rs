@@ -0,0 +1,369 @@
+//! Paints effects that follow the cursor: a fading trail of its past positions
+//! and bursts of particles when it jumps or enters insert mode. The effects
+//! only animate while there is something to show.
+
+use super::{cursor, quad};
+use crate::{
+    rendering::Motion,
+    ui::Ui,
+    util::{
+        parse_map,
+        vec2::{PixelVec, Vec2},
+        MaybeInto, Parse,
+    },
+};
+use bytemuck::{Pod, Zeroable};
+use rmpv::Value;
+use std::{collections::VecDeque, f32::consts::TAU, time::Duration};
+use wgpu::include_wgsl;
+
+pub struct Pipeline {
+    quads: quad::Pipeline,
+    trail: VecDeque<TrailPoint>,
+    particles: Vec<Particle>,
+    rng: Rng,
+    /// The cursor position from the last update
+    last_target: Option<PixelVec<f32>>,
+    /// The cursor position from the last frame
+    last_center: Option<Vec2<f32>>,
+    /// Whether the cursor is animating a move of more than one cell
+    jumping: bool,
+    /// Where the cursor jumped from, to be handled on the next frame
+    jumped_from: Option<Vec2<f32>>,
+    was_insert: bool,
+    entered_insert: bool,
+}
+
+impl Pipeline {
+    const TRAIL_LIFETIME: f32 = 0.15;
+    const MAX_PARTICLES: usize = 512;
+
+    pub fn new(device: &wgpu::Device) -> Self {
+        Self {
+            quads: quad::Pipeline::new::<PushConstants>(
+                device,
+                quad::Descriptor {
+                    label: "Cursor effects",
+                    shader: include_wgsl!("cursor_effects.wgsl"),
+                    push_constant_stages: wgpu::ShaderStages::VERTEX,
+                    instanced: true,
+                    depth: false,
+                },
+            ),
+            trail: VecDeque::new(),
+            particles: vec![],
+            rng: Rng(0x9E37_79B9),
+            last_target: None,
+            last_center: None,
+            jumping: false,
+            jumped_from: None,
+            was_insert: false,
+            entered_insert: false,
+        }
+    }
+
+    /// Notice cursor jumps and mode changes. Call after updating the cursor.
+    pub fn update(&mut self, ui: &Ui, cursor: &cursor::Pipeline, cell_size: Vec2<f32>) {
+        let is_insert = ui
+            .modes
+            .get(ui.current_mode as usize)
+            .and_then(|mode| mode.name.as_deref())
+            == Some("insert");
+        self.entered_insert |= is_insert && !self.was_insert;
+        self.was_insert = is_insert;
+
+        let target = cursor.target();
+        if let (Some(last_target), Some(target)) = (self.last_target, target) {
+            let distance = (target - last_target).0 / cell_size;
+            if distance.x.abs() > 1.5 || distance.y.abs() > 1.5 {
+                self.jumping = true;
+                self.jumped_from = self.last_center;
+            }
+        }
+        self.last_target = target;
+    }
+
+    /// Move the effects along and upload them for rendering
+    pub fn advance(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        delta_time: Duration,
+        cursor: &cursor::Pipeline,
+        settings: CursorEffectSettings,
+        cell_size: Vec2<f32>,
+    ) -> Motion {
+        let dt = delta_time.as_secs_f32();
+        let color = cursor.color();
+        let rect = cursor.rect();
+        let center = rect.map(|(position, size)| position.0 + size / 2.);
+        let moved = center.is_some() && center != self.last_center;
+
+        for point in self.trail.iter_mut() {
+            point.age += dt;
+        }
+        self.trail
+            .retain(|point| point.age < Self::TRAIL_LIFETIME && settings.trail);
+        if let (true, true, Some((position, size))) = (settings.trail, moved, rect) {
+            self.trail.push_back(TrailPoint {
+                position: position.0,
+                size,
+                age: 0.,
+            });
+        }
+
+        for particle in self.particles.iter_mut() {
+            particle.age += dt;
+            particle.velocity.y += particle.gravity * dt;
+            particle.position += particle.velocity * dt;
+        }
+        self.particles
+            .retain(|particle| particle.age < particle.lifetime);
+
+        if let (Some(style), Some(center)) = (settings.particles, center) {
+            let jumped_from = self.jumped_from.take();
+            if std::mem::take(&mut self.entered_insert) {
+                self.burst(center, cell_size);
+            }
+            match style {
+                ParticleStyle::Railgun => {
+                    if let Some(from) = jumped_from {
+                        self.railgun(from, center, cell_size);
+                    }
+                }
+                ParticleStyle::Torpedo | ParticleStyle::PixieDust if self.jumping && moved => {
+                    if let Some(last_center) = self.last_center {
+                        self.spray(style, last_center, center, cell_size);
+                    }
+                }
+                _ => {}
+            }
+        } else {
+            self.jumped_from = None;
+            self.entered_insert = false;
+        }
+        if !moved {
+            self.jumping = false;
+        }
+        self.last_center = center;
+        self.particles.truncate(Self::MAX_PARTICLES);
+
+        let trail = self.trail.iter().map(|point| Sprite {
+            position: point.position,
+            size: point.size,
+            color: with_alpha(color, 0.5 * (1. - point.age / Self::TRAIL_LIFETIME)),
+            roundness: 0.,
+            padding: [0.; 3],
+        });
+        let particles = self.particles.iter().map(|particle| Sprite {
+            position: particle.position - Vec2::splat(particle.size / 2.),
+            size: Vec2::splat(particle.size),
+            color: with_alpha(color, 1. - particle.age / particle.lifetime),
+            roundness: 1.,
+            padding: [0.; 3],
+        });
+        let sprites: Vec<_> = trail.chain(particles).collect();
+        self.quads.upload(device, queue, &sprites);
+
+        if sprites.is_empty() {
+            Motion::Still
+        } else {
+            Motion::Animating
+        }
+    }
+
+    /// Particles along the path of a jump
+    fn railgun(&mut self, from: Vec2<f32>, to: Vec2<f32>, cell_size: Vec2<f32>) {
+        let path = to - from;
+        let count = ((path.length() / cell_size.x) as usize).clamp(8, 64);
+        for i in 0..count {
+            let t = i as f32 / count as f32;
+            let velocity = self.rng.direction() * self.rng.range(0.5, 2.) * cell_size.y;
+            self.particles.push(Particle {
+                position: from + path * t,
+                velocity,
+                gravity: 0.,
+                age: 0.,
+                // Particles left behind earlier last longer
+                lifetime: self.rng.range(0.2, 0.35) + (1. - t) * 0.15,
+                size: cell_size.y * 0.15,
+            });
+        }
+    }
+
+    /// Particles left behind by the moving cursor
+    fn spray(
+        &mut self,
+        style: ParticleStyle,
+        from: Vec2<f32>,
+        to: Vec2<f32>,
+        cell_size: Vec2<f32>,
+    ) {
+        let path = to - from;
+        let length = path.length();
+        let count = ((length / cell_size.x) as usize).clamp(1, 8);
+        let backward = if length > 0. {
+            path / -length
+        } else {
+            Vec2::default()
+        };
+        for i in 0..count {
+            let position = from + path * (i as f32 / count as f32);
+            let particle = match style {
+                ParticleStyle::Torpedo => Particle {
+                    position,
+                    velocity: (backward * self.rng.range(2., 4.) + self.rng.direction())
+                        * cell_size.y,
+                    gravity: 0.,
+                    age: 0.,
+                    lifetime: self.rng.range(0.25, 0.4),
+                    size: cell_size.y * 0.18,
+                },
+                ParticleStyle::PixieDust | ParticleStyle::Railgun => Particle {
+                    position: position + self.rng.direction() * cell_size.y * 0.3,
+                    velocity: self.rng.direction() * self.rng.range(0.2, 1.) * cell_size.y,
+                    gravity: 6. * cell_size.y,
+                    age: 0.,
+                    lifetime: self.rng.range(0.5, 0.9),
+                    size: cell_size.y * 0.1,
+                },
+            };
+            self.particles.push(particle);
+        }
+    }
+
+    /// Particles flying out in every direction
+    fn burst(&mut self, center: Vec2<f32>, cell_size: Vec2<f32>) {
+        for _ in 0..16 {
+            self.particles.push(Particle {
+                position: center,
+                velocity: self.rng.direction() * self.rng.range(3., 5.) * cell_size.y,
+                gravity: 0.,
+                age: 0.,
+                lifetime: self.rng.range(0.25, 0.35),
+                size: cell_size.y * 0.12,
+            });
+        }
+    }
+
+    pub fn render(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        color_target: &wgpu::TextureView,
+        target_size: PixelVec<u32>,
+    ) {
+        let push_constants = PushConstants {
+            target_size: target_size.cast_as(),
+        };
+        self.quads
+            .render(encoder, color_target, None, push_constants);
+    }
+}
+
+/// User settings for cursor effects
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CursorEffectSettings {
+    /// Whether to leave a fading trail behind the moving cursor
+    pub trail: bool,
+    /// The particles to emit when the cursor jumps or enters insert mode
+    pub particles: Option<ParticleStyle>,
+}
+
+impl Parse for CursorEffectSettings {
+    fn parse(value: Value) -> Option<Self> {
+        let mut out = Self::default();
+        for (k, v) in parse_map(value)? {
+            match k.as_str()? {
+                "trail" => out.trail = v.maybe_into()?,
+                "particles" => out.particles = v.maybe_into()?,
+                _ => {}
+            }
+        }
+        Some(out)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParticleStyle {
+    /// Particles scattered along the path of a jump
+    Railgun,
+    /// A wake of particles behind the moving cursor
+    Torpedo,
+    /// Sparkles that drift down behind the moving cursor
+    PixieDust,
+}
+
+impl Parse for ParticleStyle {
+    fn parse(value: Value) -> Option<Self> {
+        Some(match value.as_str()? {
+            "railgun" => Self::Railgun,
+            "torpedo" => Self::Torpedo,
+            "pixie_dust" => Self::PixieDust,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TrailPoint {
+    position: Vec2<f32>,
+    size: Vec2<f32>,
+    age: f32,
+}
+
+/// A particle with its position and velocity in pixels
+#[derive(Debug, Clone, Copy)]
+struct Particle {
+    position: Vec2<f32>,
+    velocity: Vec2<f32>,
+    gravity: f32,
+    age: f32,
+    lifetime: f32,
+    size: f32,
+}
+
+/// A xorshift random number generator. Effects only need to look random.
+#[derive(Debug, Clone, Copy)]
+struct Rng(u32);
+
+impl Rng {
+    fn next(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0 as f32 / u32::MAX as f32
+    }
+
+    fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + self.next() * (max - min)
+    }
+
+    /// A unit vector in a random direction
+    fn direction(&mut self) -> Vec2<f32> {
+        let angle = self.next() * TAU;
+        Vec2::new(angle.cos(), angle.sin())
+    }
+}
+
+fn with_alpha(color: [f32; 4], alpha: f32) -> [f32; 4] {
+    let [r, g, b, a] = color;
+    [r, g, b, a * alpha]
+}
+
+/// A rectangle in pixels, optionally drawn as a circle
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Pod, Zeroable)]
+struct Sprite {
+    position: Vec2<f32>,
+    size: Vec2<f32>,
+    color: [f32; 4],
+    /// 0 for a rectangle or 1 for a circle
+    roundness: f32,
+    padding: [f32; 3],
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, Pod, Zeroable)]
+struct PushConstants {
+    target_size: PixelVec<f32>,
+}
@@ -4,6 +4,7 @@ struct GridInfo {
     g: f32,
     b: f32,
     a: f32,
+    dim: f32,
 }
 
 var<push_constant> info: GridInfo;
@@ -23,7 +24,7 @@ fn vs_main(
     );
 
     var out: VertexOutput;
-    out.color = vec4<f32>(info.r, info.g, info.b, info.a);
+    out.color = vec4<f32>(dim(vec3<f32>(info.r, info.g, info.b), info.dim), info.a);
     out.clip_position = vec4<f32>(
         vec2<f32>(tex_coord) * vec2<f32>(2.0, 2.0) - vec2<f32>(1.0, 1.0),
         info.z, 
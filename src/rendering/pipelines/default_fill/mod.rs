@@ -5,6 +5,7 @@
 //! LessEqual depth compare and they will be correctly masked, even with
 //! front-to-back rendering and overlapping windows.
 
+use super::with_dim;
 use crate::{
     rendering::{
         text::{set_scissor, Text},
@@ -13,7 +14,6 @@ use crate::{
     util::vec2::{PixelVec, Vec2},
 };
 use bytemuck::{checked::cast_slice, Pod, Zeroable};
-use wgpu::include_wgsl;
 
 pub struct Pipeline {
     pipeline: wgpu::RenderPipeline,
@@ -21,7 +21,10 @@ pub struct Pipeline {
 
 impl Pipeline {
     pub fn new(device: &wgpu::Device, texture_format: wgpu::TextureFormat) -> Self {
-        let shader = device.create_shader_module(include_wgsl!("default_fill.wgsl"));
+        let shader = device.create_shader_module(with_dim(
+            "default_fill.wgsl",
+            include_str!("default_fill.wgsl"),
+        ));
 
         let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: Some("Default fill pipeline layout"),
@@ -130,6 +133,7 @@ impl Pipeline {
                     g: clear_color[1],
                     b: clear_color[2],
                     a: clear_color[3],
+                    dim: grid.dim(),
                 }
                 .set(&mut render_pass);
                 render_pass.draw(0..6, 0..1);
@@ -146,6 +150,7 @@ pub struct PushConstants {
     g: f32,
     b: f32,
     a: f32,
+    dim: f32,
 }
 
 impl PushConstants {
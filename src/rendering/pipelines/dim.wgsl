@@ -0,0 +1,8 @@
+// Appended to the shaders for each part of a window so that they all dim the
+// same way
+
+// Darkens and desaturates the colors of windows that don't have focus
+fn dim(color: vec3<f32>, amount: f32) -> vec3<f32> {
+    let luma = dot(color, vec3<f32>(0.2126, 0.7152, 0.0722));
+    return mix(color, vec3<f32>(luma), amount) * (1.0 - amount);
+}
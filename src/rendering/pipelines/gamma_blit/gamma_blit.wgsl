@@ -1,6 +1,8 @@
 struct Constants {
     src_size: vec2<i32>,
     dst_size: vec2<i32>,
+    // Where the top-left of the source goes in the destination
+    offset: vec2<i32>,
     transparent: f32,
 }
 
@@ -24,8 +26,7 @@ fn vs_main(
         f32(in_vertex_index % 2u),
         f32(((in_vertex_index + 5u) % 6u) / 3u),
     );
-    let two = vec2<i32>(2, 2);
-    let offset = vec2<f32>((constants.dst_size - constants.src_size) / two);
+    let offset = vec2<f32>(constants.offset);
     let src_size = vec2<f32>(constants.src_size);
     let dst_size = vec2<f32>(constants.dst_size);
     let position = (out.uv * src_size + offset) / dst_size;
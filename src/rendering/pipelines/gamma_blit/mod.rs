@@ -76,6 +76,7 @@ impl Pipeline {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn update(
         &mut self,
         device: &wgpu::Device,
@@ -83,6 +84,7 @@ impl Pipeline {
         src_tex: &wgpu::TextureView,
         src_size: PixelVec<u32>,
         dst_size: PixelVec<u32>,
+        offset: PixelVec<u32>,
         transparent: bool,
     ) {
         self.bind_group = bind_group(device, &self.bind_group_layout, &self.sampler, src_tex);
@@ -90,6 +92,7 @@ impl Pipeline {
         self.push_constants_vertex = PushConstantsVertex {
             src_size: src_size.try_cast().unwrap(),
             dst_size: dst_size.try_cast().unwrap(),
+            offset: offset.try_cast().unwrap(),
         };
         self.transparent = transparent;
     }
@@ -210,6 +213,7 @@ fn bind_group(
 pub struct PushConstantsVertex {
     src_size: PixelVec<i32>,
     dst_size: PixelVec<i32>,
+    offset: PixelVec<i32>,
 }
 
 #[repr(C)]
@@ -0,0 +1,66 @@
+struct PushConstants {
+    target_size: vec2<f32>,
+    position: vec2<f32>,
+    size: vec2<f32>,
+    scale: f32,
+    opacity: f32,
+    z: f32,
+    corner_radius: f32,
+}
+
+@group(0) @binding(0)
+var tex: texture_2d<f32>;
+@group(0) @binding(1)
+var tex_sampler: sampler;
+var<push_constant> push_constants: PushConstants;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) opacity: f32,
+    // Pixels from the center of the unscaled layer
+    @location(2) local: vec2<f32>,
+    @location(3) half_size: vec2<f32>,
+    @location(4) corner_radius: f32,
+}
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) in_vertex_index: u32,
+) -> VertexOutput {
+    let corner = vec2<f32>(
+        f32(in_vertex_index % 2u),
+        f32(((in_vertex_index + 5u) % 6u) / 3u),
+    );
+    let size = push_constants.size;
+    let center = push_constants.position + size / 2.0;
+    let position = center + (corner - 0.5) * size * push_constants.scale;
+
+    var out: VertexOutput;
+    out.uv = (push_constants.position + corner * size) / push_constants.target_size;
+    out.opacity = push_constants.opacity;
+    out.local = (corner - 0.5) * size;
+    out.half_size = size / 2.0;
+    out.corner_radius = min(push_constants.corner_radius, min(size.x, size.y) / 2.0);
+    out.clip_position = vec4<f32>(
+        position / push_constants.target_size * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0),
+        push_constants.z,
+        1.0,
+    );
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Signed distance to the edge of the rounded rectangle
+    let q = abs(in.local) - in.half_size + in.corner_radius;
+    let distance = length(max(q, vec2<f32>(0.0))) + min(max(q.x, q.y), 0.0) - in.corner_radius;
+    let coverage = clamp(0.5 - distance, 0.0, 1.0);
+    // Leave the depth alone outside the corners so grids below still show
+    if coverage <= 0.0 {
+        discard;
+    }
+
+    let sample = textureSampleLevel(tex, tex_sampler, in.uv, 0.0);
+    return vec4<f32>(sample.rgb, sample.a * in.opacity * coverage);
+}
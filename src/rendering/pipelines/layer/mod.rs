@@ -0,0 +1,271 @@
+//! Composites a grid that was drawn on its own layer onto the frame with some
+//! opacity, scale, and rounded corners, such as while a floating window fades
+//! in. The layer writes depth so that grids below it don't draw over it
+//! afterwards. The text of the layer is composited the same way into the
+//! monochrome target so that the cursor shows the text where it appears on
+//! screen.
+
+use crate::{rendering::texture::Texture, util::vec2::PixelVec};
+use bytemuck::{cast_slice, Pod, Zeroable};
+use wgpu::include_wgsl;
+
+pub struct Pipeline {
+    pipeline: wgpu::RenderPipeline,
+    monochrome_pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    bind_group: wgpu::BindGroup,
+    monochrome_bind_group: wgpu::BindGroup,
+    sampler: wgpu::Sampler,
+}
+
+impl Pipeline {
+    pub fn new(
+        device: &wgpu::Device,
+        texture_view: &wgpu::TextureView,
+        monochrome_texture_view: &wgpu::TextureView,
+    ) -> Self {
+        let shader = device.create_shader_module(include_wgsl!("layer.wgsl"));
+
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Layer bind group layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Layer pipeline layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[wgpu::PushConstantRange {
+                stages: wgpu::ShaderStages::VERTEX,
+                range: 0..PushConstants::SIZE,
+            }],
+        });
+
+        // Within the layer, the text of grids below is covered by the layer's
+        // text rather than blended with it
+        let monochrome_pipeline =
+            pipeline(device, &pipeline_layout, &shader, wgpu::BlendState::REPLACE);
+        let pipeline = pipeline(
+            device,
+            &pipeline_layout,
+            &shader,
+            wgpu::BlendState::ALPHA_BLENDING,
+        );
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Layer sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        Self {
+            bind_group: bind_group(device, &bind_group_layout, texture_view, &sampler),
+            monochrome_bind_group: bind_group(
+                device,
+                &bind_group_layout,
+                monochrome_texture_view,
+                &sampler,
+            ),
+            pipeline,
+            monochrome_pipeline,
+            bind_group_layout,
+            sampler,
+        }
+    }
+
+    pub fn update(
+        &mut self,
+        device: &wgpu::Device,
+        texture_view: &wgpu::TextureView,
+        monochrome_texture_view: &wgpu::TextureView,
+    ) {
+        self.bind_group = bind_group(device, &self.bind_group_layout, texture_view, &self.sampler);
+        self.monochrome_bind_group = bind_group(
+            device,
+            &self.bind_group_layout,
+            monochrome_texture_view,
+            &self.sampler,
+        );
+    }
+
+    /// Composite the part of the layer covered by the given rectangle in
+    /// pixels. The layer's text goes into the monochrome target with the same
+    /// placement, opacity, and corners as the layer goes into the color
+    /// target. Otherwise, the cursor would show text that isn't on screen.
+    #[allow(clippy::too_many_arguments)]
+    pub fn render(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        color_target: &wgpu::TextureView,
+        monochrome_target: &wgpu::TextureView,
+        depth_target: &wgpu::TextureView,
+        target_size: PixelVec<u32>,
+        position: PixelVec<i32>,
+        size: PixelVec<u32>,
+        z: f32,
+        opacity: f32,
+        scale: f32,
+        corner_radius: f32,
+    ) {
+        let push_constants = PushConstants {
+            target_size: target_size.cast_as(),
+            position: position.cast_as(),
+            size: size.cast_as(),
+            scale,
+            opacity,
+            z,
+            corner_radius,
+        };
+        // The monochrome pass goes first because the color pass writes the
+        // depth that would otherwise hide the layer from itself
+        for (target, pipeline, bind_group) in [
+            (
+                monochrome_target,
+                &self.monochrome_pipeline,
+                &self.monochrome_bind_group,
+            ),
+            (color_target, &self.pipeline, &self.bind_group),
+        ] {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Layer render pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: target,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: depth_target,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    }),
+                    stencil_ops: None,
+                }),
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+
+            render_pass.set_pipeline(pipeline);
+            render_pass.set_bind_group(0, bind_group, &[]);
+            push_constants.set(&mut render_pass);
+            render_pass.draw(0..6, 0..1);
+        }
+    }
+}
+
+fn pipeline(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    shader: &wgpu::ShaderModule,
+    blend: wgpu::BlendState,
+) -> wgpu::RenderPipeline {
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Layer render pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: shader,
+            entry_point: "vs_main",
+            buffers: &[],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: shader,
+            entry_point: "fs_main",
+            targets: &[Some(wgpu::ColorTargetState {
+                format: Texture::LINEAR_FORMAT,
+                blend: Some(blend),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: None,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            unclipped_depth: false,
+            conservative: false,
+        },
+        depth_stencil: Some(wgpu::DepthStencilState {
+            format: Texture::DEPTH_FORMAT,
+            depth_write_enabled: true,
+            depth_compare: wgpu::CompareFunction::LessEqual,
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+        }),
+        multisample: wgpu::MultisampleState {
+            count: 1,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        multiview: None,
+        cache: None,
+    })
+}
+
+fn bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    texture_view: &wgpu::TextureView,
+    sampler: &wgpu::Sampler,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Layer bind group"),
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(texture_view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(sampler),
+            },
+        ],
+    })
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, Pod, Zeroable)]
+struct PushConstants {
+    target_size: PixelVec<f32>,
+    position: PixelVec<f32>,
+    size: PixelVec<f32>,
+    scale: f32,
+    opacity: f32,
+    z: f32,
+    corner_radius: f32,
+}
+
+impl PushConstants {
+    const SIZE: u32 = std::mem::size_of::<Self>() as u32;
+
+    fn set(self, render_pass: &mut wgpu::RenderPass) {
+        render_pass.set_push_constants(wgpu::ShaderStages::VERTEX, 0, cast_slice(&[self]));
+    }
+}
@@ -3,6 +3,7 @@ struct GridInfo {
     offset: vec2<i32>,
     grid_width: i32,
     z: f32,
+    dim: f32,
 }
 
 struct Line {
@@ -38,7 +39,7 @@ fn vs_main(
     let size = vec2<u32>(line.w, line.h);
 
     var out: VertexOutput;
-    out.color = vec4<f32>(line.r, line.g, line.b, 1.0);
+    out.color = vec4<f32>(dim(vec3<f32>(line.r, line.g, line.b), grid_info.dim), 1.0);
     out.clip_position = vec4<f32>(
         vec2<f32>(position + grid_info.offset + vec2<i32>(tex_coord * size)) / 
         vec2<f32>(grid_info.target_size) * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0),
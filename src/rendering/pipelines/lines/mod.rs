@@ -1,5 +1,6 @@
 //! Paints text underlines.
 
+use super::with_dim;
 use crate::{
     rendering::{
         text::{set_scissor, Text},
@@ -8,7 +9,6 @@ use crate::{
     util::vec2::{PixelVec, Vec2},
 };
 use bytemuck::{checked::cast_slice, Pod, Zeroable};
-use wgpu::include_wgsl;
 
 pub struct Pipeline {
     pipeline: wgpu::RenderPipeline,
@@ -20,7 +20,8 @@ impl Pipeline {
         grid_bind_group_layout: &wgpu::BindGroupLayout,
         texture_format: wgpu::TextureFormat,
     ) -> Self {
-        let shader = device.create_shader_module(include_wgsl!("lines.wgsl"));
+        let shader =
+            device.create_shader_module(with_dim("lines.wgsl", include_str!("lines.wgsl")));
 
         let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: Some("Lines pipeline layout"),
@@ -126,6 +127,8 @@ impl Pipeline {
                     offset: offset + scroll_offset + PixelVec::new(0, underline_offset + 2),
                     grid_width: grid.size().0.x.try_into().unwrap(),
                     z,
+                    dim: grid.dim(),
+                    padding: 0,
                 }
                 .set(&mut render_pass);
                 render_pass.draw(0..grid.lines_count() * 6, 0..1);
@@ -141,6 +144,8 @@ pub struct PushConstants {
     pub offset: PixelVec<i32>,
     pub grid_width: i32,
     pub z: f32,
+    pub dim: f32,
+    pub padding: u32,
 }
 
 impl PushConstants {
@@ -1,18 +1,35 @@
 pub mod blend;
 pub mod cell_fill;
 pub mod cursor;
+pub mod cursor_effects;
 pub mod default_fill;
 pub mod gamma_blit;
+pub mod layer;
 pub mod lines;
+pub mod overlay;
 pub mod png_blit;
+pub mod post_process;
+mod quad;
+pub mod shadow;
 pub mod text;
 
 use super::{targets::Targets, texture::Texture, wgpu_context::WgpuContext};
 use crate::{text::cache::FontCache, ui::Ui, util::vec2::Vec2};
 
+const DIM: &str = include_str!("dim.wgsl");
+
+/// Shader source with the shared dim function appended
+fn with_dim(label: &'static str, source: &str) -> wgpu::ShaderModuleDescriptor<'static> {
+    wgpu::ShaderModuleDescriptor {
+        label: Some(label),
+        source: wgpu::ShaderSource::Wgsl(format!("{source}\n{DIM}").into()),
+    }
+}
+
 pub struct Pipelines {
     pub cursor: cursor::Pipeline,
     pub cmdline_cursor: cursor::Pipeline,
+    pub cursor_effects: cursor_effects::Pipeline,
     pub blend: blend::Pipeline,
     pub default_fill: default_fill::Pipeline,
     pub cell_fill: cell_fill::Pipeline,
@@ -21,6 +38,10 @@ pub struct Pipelines {
     pub gamma_blit_final: gamma_blit::Pipeline,
     pub blit_png: png_blit::Pipeline,
     pub lines: lines::Pipeline,
+    pub layer: layer::Pipeline,
+    pub shadow: shadow::Pipeline,
+    pub post_process: post_process::Pipeline,
+    pub overlay: overlay::Pipeline,
 }
 
 impl Pipelines {
@@ -33,7 +54,12 @@ impl Pipelines {
         Pipelines {
             cursor: cursor::Pipeline::new(device, &targets.monochrome.view),
             cmdline_cursor: cursor::Pipeline::new(device, &targets.monochrome.view),
-            blend: blend::Pipeline::new(device, &targets.color.view),
+            cursor_effects: cursor_effects::Pipeline::new(device),
+            blend: blend::Pipeline::new(
+                device,
+                &targets.color.view,
+                &targets.layer_monochrome.view,
+            ),
             default_fill: default_fill::Pipeline::new(device, Texture::LINEAR_FORMAT),
             cell_fill: cell_fill::Pipeline::new(
                 device,
@@ -43,6 +69,14 @@ impl Pipelines {
             monochrome: text::Pipeline::new(device, text_bind_group_layout, text::Kind::Monochrome),
             emoji: text::Pipeline::new(device, text_bind_group_layout, text::Kind::Emoji),
             lines: lines::Pipeline::new(device, text_bind_group_layout, Texture::LINEAR_FORMAT),
+            layer: layer::Pipeline::new(
+                device,
+                &targets.layer.view,
+                &targets.layer_monochrome.view,
+            ),
+            shadow: shadow::Pipeline::new(device),
+            post_process: post_process::Pipeline::new(device, &targets.color.view),
+            overlay: overlay::Pipeline::new(device),
             gamma_blit_final: gamma_blit::Pipeline::new(
                 device,
                 surface_config.format,
@@ -78,6 +112,7 @@ impl Pipelines {
             cell_size,
             &targets.monochrome.view,
         );
+        self.cursor_effects.update(ui, &self.cursor, cell_size);
         self.monochrome.update(
             &wgpu_context.device,
             &wgpu_context.queue,
@@ -85,7 +120,18 @@ impl Pipelines {
         );
         self.emoji
             .update(&wgpu_context.device, &wgpu_context.queue, &font_cache.emoji);
-        self.blend
-            .update(&wgpu_context.device, &targets.monochrome.view);
+        self.blend.update(
+            &wgpu_context.device,
+            &targets.monochrome.view,
+            &targets.layer_monochrome.view,
+        );
+        self.layer.update(
+            &wgpu_context.device,
+            &targets.layer.view,
+            &targets.layer_monochrome.view,
+        );
+        self.post_process
+            .update(&wgpu_context.device, &targets.color.view);
+        self.post_process.update_mode(ui);
     }
 }
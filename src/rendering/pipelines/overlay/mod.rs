@@ -0,0 +1,95 @@
+//! Paints translucent rectangles over the finished frame, such as to highlight
+//! a drop target.
+
+use super::quad;
+use crate::util::vec2::{PixelVec, Vec2};
+use bytemuck::{Pod, Zeroable};
+use wgpu::include_wgsl;
+
+pub struct Pipeline {
+    quads: quad::Pipeline,
+}
+
+impl Pipeline {
+    pub fn new(device: &wgpu::Device) -> Self {
+        Self {
+            quads: quad::Pipeline::new::<PushConstants>(
+                device,
+                quad::Descriptor {
+                    label: "Overlay",
+                    shader: include_wgsl!("overlay.wgsl"),
+                    push_constant_stages: wgpu::ShaderStages::VERTEX,
+                    instanced: true,
+                    depth: false,
+                },
+            ),
+        }
+    }
+
+    pub fn update(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, rects: &[Rect]) {
+        self.quads.upload(device, queue, rects);
+    }
+
+    pub fn render(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        color_target: &wgpu::TextureView,
+        target_size: PixelVec<u32>,
+    ) {
+        let push_constants = PushConstants {
+            target_size: target_size.cast_as(),
+        };
+        self.quads
+            .render(encoder, color_target, None, push_constants);
+    }
+}
+
+/// A rectangle in pixels with a linear color
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Pod, Zeroable)]
+pub struct Rect {
+    pub position: Vec2<f32>,
+    pub size: Vec2<f32>,
+    pub color: [f32; 4],
+}
+
+impl Rect {
+    /// The outline of the rectangle as four rectangles of the given width
+    pub fn outline(self, width: f32) -> [Rect; 4] {
+        let Self {
+            position,
+            size,
+            color,
+        } = self;
+        let horizontal = Vec2::new(size.x, width);
+        let vertical = Vec2::new(width, size.y);
+        [
+            Rect {
+                position,
+                size: horizontal,
+                color,
+            },
+            Rect {
+                position: position + Vec2::new(0., size.y - width),
+                size: horizontal,
+                color,
+            },
+            Rect {
+                position,
+                size: vertical,
+                color,
+            },
+            Rect {
+                position: position + Vec2::new(size.x - width, 0.),
+                size: vertical,
+                color,
+            },
+        ]
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, Pod, Zeroable)]
+struct PushConstants {
+    target_size: PixelVec<f32>,
+}
@@ -0,0 +1,44 @@
+struct PushConstants {
+    target_size: vec2<f32>,
+}
+
+struct Rect {
+    position: vec2<f32>,
+    size: vec2<f32>,
+    color: vec4<f32>,
+}
+
+@group(0) @binding(0)
+var<storage, read> rects: array<Rect>;
+var<push_constant> push_constants: PushConstants;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) color: vec4<f32>,
+}
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) in_vertex_index: u32,
+) -> VertexOutput {
+    let rect = rects[in_vertex_index / 6u];
+    let tex_coord = vec2<f32>(
+        f32(in_vertex_index % 2u),
+        f32(((in_vertex_index + 5u) % 6u) / 3u),
+    );
+    let position = rect.position + tex_coord * rect.size;
+
+    var out: VertexOutput;
+    out.color = rect.color;
+    out.clip_position = vec4<f32>(
+        position / push_constants.target_size * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0),
+        0.0,
+        1.0,
+    );
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return in.color;
+}
@@ -0,0 +1,327 @@
+//! Runs a WGSL fragment shader from the user over the finished frame. The
+//! shader defines `fs_main`, which receives the `VertexOutput` from
+//! prelude.wgsl and can sample the frame and read the uniforms declared
+//! there. The shader is reloaded when its file changes.
+
+use crate::{
+    rendering::{targets::Targets, texture::Texture},
+    ui::Ui,
+    util::vec2::PixelVec,
+};
+use bytemuck::{cast_slice, Pod, Zeroable};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::{Instant, SystemTime},
+};
+
+const PRELUDE: &str = include_str!("prelude.wgsl");
+
+pub struct Pipeline {
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline_layout: wgpu::PipelineLayout,
+    bind_group: wgpu::BindGroup,
+    sampler: wgpu::Sampler,
+    shader: Option<UserShader>,
+    mode: u32,
+}
+
+/// A shader loaded from a file
+struct UserShader {
+    path: PathBuf,
+    /// When the file was last modified as of the last load
+    modified: Option<SystemTime>,
+    /// None if the shader has not compiled yet
+    pipeline: Option<wgpu::RenderPipeline>,
+    loaded_at: Instant,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Failed to read {}: {1}", .0.display())]
+    Read(PathBuf, io::Error),
+    #[error("Failed to compile {}:\n{1}", .0.display())]
+    Compile(PathBuf, String),
+}
+
+impl Pipeline {
+    pub fn new(device: &wgpu::Device, frame: &wgpu::TextureView) -> Self {
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Post-process bind group layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Post-process pipeline layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[wgpu::PushConstantRange {
+                stages: wgpu::ShaderStages::FRAGMENT,
+                range: 0..PushConstants::SIZE,
+            }],
+        });
+
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Post-process sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        Self {
+            bind_group: bind_group(device, &bind_group_layout, frame, &sampler),
+            bind_group_layout,
+            pipeline_layout,
+            sampler,
+            shader: None,
+            mode: Mode::Normal as u32,
+        }
+    }
+
+    pub fn update(&mut self, device: &wgpu::Device, frame: &wgpu::TextureView) {
+        self.bind_group = bind_group(device, &self.bind_group_layout, frame, &self.sampler);
+    }
+
+    pub fn update_mode(&mut self, ui: &Ui) {
+        self.mode = ui
+            .modes
+            .get(ui.current_mode as usize)
+            .and_then(|mode| mode.name.as_deref())
+            .map_or(Mode::Other, Mode::from_name) as u32;
+    }
+
+    /// Whether a shader is set, even if it failed to compile
+    pub fn is_active(&self) -> bool {
+        self.shader.is_some()
+    }
+
+    /// Use the shader at the given path. If it fails to compile, the frame is
+    /// left as is until the file is fixed.
+    pub fn load(&mut self, device: &wgpu::Device, path: PathBuf) -> Result<(), Error> {
+        let shader = self.shader.insert(UserShader {
+            modified: modified(&path),
+            path,
+            pipeline: None,
+            loaded_at: Instant::now(),
+        });
+        shader.pipeline = Some(compile(device, &self.pipeline_layout, &shader.path)?);
+        Ok(())
+    }
+
+    pub fn unload(&mut self) {
+        self.shader = None;
+    }
+
+    /// Recompile the shader if its file has changed since it was loaded.
+    /// Returns whether it was reloaded. The previous version stays in use if
+    /// the new one fails to compile.
+    pub fn reload(&mut self, device: &wgpu::Device) -> Result<bool, Error> {
+        let Some(shader) = &mut self.shader else {
+            return Ok(false);
+        };
+        let modified = modified(&shader.path);
+        if modified == shader.modified {
+            return Ok(false);
+        }
+        shader.modified = modified;
+        shader.pipeline = Some(compile(device, &self.pipeline_layout, &shader.path)?);
+        shader.loaded_at = Instant::now();
+        Ok(true)
+    }
+
+    /// Run the shader over the color target. Does nothing if there is no
+    /// compiled shader.
+    pub fn render(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        targets: &Targets,
+        target_size: PixelVec<u32>,
+        cursor: Option<PixelVec<f32>>,
+    ) {
+        let Some(shader) = &self.shader else {
+            return;
+        };
+        let Some(pipeline) = &shader.pipeline else {
+            return;
+        };
+
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Post-process render pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &targets.post.view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+
+            render_pass.set_pipeline(pipeline);
+            render_pass.set_bind_group(0, &self.bind_group, &[]);
+            PushConstants {
+                resolution: target_size.cast_as(),
+                cursor: cursor.unwrap_or(PixelVec::new(-1., -1.)),
+                time: shader.loaded_at.elapsed().as_secs_f32(),
+                mode: self.mode,
+            }
+            .set(&mut render_pass);
+            render_pass.draw(0..6, 0..1);
+        }
+
+        // Put the result back so that the rest of the frame is drawn over it
+        encoder.copy_texture_to_texture(
+            targets.post.texture.as_image_copy(),
+            targets.color.texture.as_image_copy(),
+            targets.post.texture.size(),
+        );
+    }
+}
+
+/// The mode given to the shader, matching the MODE_ constants in the prelude
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Normal,
+    Insert,
+    Visual,
+    Replace,
+    Cmdline,
+    Other,
+}
+
+impl Mode {
+    fn from_name(name: &str) -> Self {
+        match name {
+            "normal" | "operator" => Self::Normal,
+            "insert" | "showmatch" => Self::Insert,
+            "visual" | "visual_select" => Self::Visual,
+            "replace" => Self::Replace,
+            name if name.starts_with("cmdline") => Self::Cmdline,
+            _ => Self::Other,
+        }
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+fn compile(
+    device: &wgpu::Device,
+    layout: &wgpu::PipelineLayout,
+    path: &Path,
+) -> Result<wgpu::RenderPipeline, Error> {
+    let source = fs::read_to_string(path).map_err(|e| Error::Read(path.to_owned(), e))?;
+    let source = format!("{source}\n{PRELUDE}");
+
+    // Catch errors rather than letting wgpu panic on them
+    device.push_error_scope(wgpu::ErrorFilter::Validation);
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Post-process shader"),
+        source: wgpu::ShaderSource::Wgsl(source.into()),
+    });
+    let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: Some("Post-process render pipeline"),
+        layout: Some(layout),
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: "vs_main",
+            buffers: &[],
+            compilation_options: Default::default(),
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: "fs_main",
+            targets: &[Some(wgpu::ColorTargetState {
+                format: Texture::LINEAR_FORMAT,
+                blend: Some(wgpu::BlendState::REPLACE),
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: None,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            unclipped_depth: false,
+            conservative: false,
+        },
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState {
+            count: 1,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        multiview: None,
+        cache: None,
+    });
+    match pollster::block_on(device.pop_error_scope()) {
+        Some(error) => Err(Error::Compile(path.to_owned(), error.to_string())),
+        None => Ok(pipeline),
+    }
+}
+
+fn bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    frame: &wgpu::TextureView,
+    sampler: &wgpu::Sampler,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Post-process bind group"),
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(frame),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(sampler),
+            },
+        ],
+    })
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, Pod, Zeroable)]
+struct PushConstants {
+    resolution: PixelVec<f32>,
+    cursor: PixelVec<f32>,
+    time: f32,
+    mode: u32,
+}
+
+impl PushConstants {
+    const SIZE: u32 = std::mem::size_of::<Self>() as u32;
+
+    fn set(self, render_pass: &mut wgpu::RenderPass) {
+        render_pass.set_push_constants(wgpu::ShaderStages::FRAGMENT, 0, cast_slice(&[self]));
+    }
+}
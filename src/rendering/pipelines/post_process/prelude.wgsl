@@ -0,0 +1,51 @@
+// Declarations available to post-process shaders. This is appended to the
+// user's shader so that line numbers in errors match their file.
+
+const MODE_NORMAL: u32 = 0u;
+const MODE_INSERT: u32 = 1u;
+const MODE_VISUAL: u32 = 2u;
+const MODE_REPLACE: u32 = 3u;
+const MODE_CMDLINE: u32 = 4u;
+const MODE_OTHER: u32 = 5u;
+
+struct Uniforms {
+    // The size of the frame in pixels
+    resolution: vec2<f32>,
+    // The center of the cursor in pixels, or -1 if it is hidden
+    cursor: vec2<f32>,
+    // Seconds since the shader was loaded
+    time: f32,
+    // One of the MODE_ constants
+    mode: u32,
+}
+
+var<push_constant> uniforms: Uniforms;
+
+// The frame so far, in sRGB
+@group(0) @binding(0)
+var frame: texture_2d<f32>;
+@group(0) @binding(1)
+var frame_sampler: sampler;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    // From (0, 0) at the top left to (1, 1) at the bottom right
+    @location(0) uv: vec2<f32>,
+}
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) in_vertex_index: u32,
+) -> VertexOutput {
+    var out: VertexOutput;
+    out.uv = vec2<f32>(
+        f32(in_vertex_index % 2u),
+        f32(((in_vertex_index + 5u) % 6u) / 3u),
+    );
+    out.position = vec4<f32>(
+        out.uv * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0),
+        0.0,
+        1.0,
+    );
+    return out;
+}
@@ -0,0 +1,225 @@
+//! Shared setup for pipelines that draw rectangles in screen space. Each quad
+//! is six vertices with no vertex buffer. The shader positions them from its
+//! push constants and, for pipelines with many quads, from per-quad data in a
+//! storage buffer at group 0, binding 0.
+
+use crate::rendering::texture::Texture;
+use bytemuck::{cast_slice, Pod};
+use std::num::NonZeroU64;
+
+pub struct Pipeline {
+    label: &'static str,
+    pipeline: wgpu::RenderPipeline,
+    push_constant_stages: wgpu::ShaderStages,
+    instances: Option<Instances>,
+}
+
+pub struct Descriptor<'a> {
+    /// The name of the pipeline, used to label its GPU resources
+    pub label: &'static str,
+    pub shader: wgpu::ShaderModuleDescriptor<'a>,
+    pub push_constant_stages: wgpu::ShaderStages,
+    /// Whether quads read their data from a storage buffer rather than there
+    /// being one quad described by the push constants
+    pub instanced: bool,
+    /// Whether quads are depth tested against the grids
+    pub depth: bool,
+}
+
+/// Per-quad data in a storage buffer
+struct Instances {
+    bind_group_layout: wgpu::BindGroupLayout,
+    bind_group: Option<wgpu::BindGroup>,
+    buffer: Option<wgpu::Buffer>,
+    buffer_capacity: u64,
+    count: u32,
+}
+
+impl Pipeline {
+    /// Create a pipeline whose push constants are a `P`
+    pub fn new<P: Pod>(device: &wgpu::Device, descriptor: Descriptor) -> Self {
+        let Descriptor {
+            label,
+            shader,
+            push_constant_stages,
+            instanced,
+            depth,
+        } = descriptor;
+        let shader = device.create_shader_module(shader);
+
+        let instances = instanced.then(|| Instances {
+            bind_group_layout: device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some(&format!("{label} bind group layout")),
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }],
+            }),
+            bind_group: None,
+            buffer: None,
+            buffer_capacity: 0,
+            count: 0,
+        });
+
+        let bind_group_layouts: Vec<_> = instances
+            .iter()
+            .map(|instances| &instances.bind_group_layout)
+            .collect();
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some(&format!("{label} pipeline layout")),
+            bind_group_layouts: &bind_group_layouts,
+            push_constant_ranges: &[wgpu::PushConstantRange {
+                stages: push_constant_stages,
+                range: 0..std::mem::size_of::<P>() as u32,
+            }],
+        });
+
+        let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(&format!("{label} render pipeline")),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: Texture::LINEAR_FORMAT,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: depth.then(|| wgpu::DepthStencilState {
+                format: Texture::DEPTH_FORMAT,
+                depth_write_enabled: false,
+                depth_compare: wgpu::CompareFunction::LessEqual,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+            cache: None,
+        });
+
+        Self {
+            label,
+            pipeline,
+            push_constant_stages,
+            instances,
+        }
+    }
+
+    /// Set the data for each quad. Only for instanced pipelines.
+    pub fn upload<T: Pod>(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, quads: &[T]) {
+        let label = self.label;
+        let instances = self
+            .instances
+            .as_mut()
+            .expect("Uploaded quads to a pipeline that isn't instanced");
+        instances.count = quads.len().try_into().unwrap();
+        let data: &[u8] = cast_slice(quads);
+        let Some(size) = NonZeroU64::new(data.len() as u64) else {
+            instances.bind_group = None;
+            return;
+        };
+
+        if size.get() > instances.buffer_capacity {
+            instances.buffer_capacity = size.get() * 2;
+            instances.buffer = Some(device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some(&format!("{label} buffer")),
+                size: instances.buffer_capacity,
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            }));
+        }
+
+        let Some(buffer) = instances.buffer.as_ref() else {
+            return;
+        };
+        queue.write_buffer(buffer, 0, data);
+        instances.bind_group = Some(device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some(&format!("{label} bind group")),
+            layout: &instances.bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                    buffer,
+                    offset: 0,
+                    size: Some(size),
+                }),
+            }],
+        }));
+    }
+
+    /// Draw the uploaded quads, or the one quad for pipelines that aren't
+    /// instanced, over the color target
+    pub fn render<P: Pod>(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        color_target: &wgpu::TextureView,
+        depth_target: Option<&wgpu::TextureView>,
+        push_constants: P,
+    ) {
+        let (bind_group, count) = match &self.instances {
+            Some(instances) => match &instances.bind_group {
+                Some(bind_group) => (Some(bind_group), instances.count),
+                None => return,
+            },
+            None => (None, 1),
+        };
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some(&format!("{} render pass", self.label)),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: color_target,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: depth_target.map(|view| {
+                wgpu::RenderPassDepthStencilAttachment {
+                    view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    }),
+                    stencil_ops: None,
+                }
+            }),
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        render_pass.set_pipeline(&self.pipeline);
+        if let Some(bind_group) = bind_group {
+            render_pass.set_bind_group(0, bind_group, &[]);
+        }
+        render_pass.set_push_constants(self.push_constant_stages, 0, cast_slice(&[push_constants]));
+        render_pass.draw(0..count * 6, 0..1);
+    }
+}
@@ -0,0 +1,73 @@
+//! Paints soft shadows beneath floating windows. Each shadow is drawn just
+//! behind its window in depth so that the window and any grids above it cover
+//! the shadow while the grids below are darkened.
+
+use super::quad;
+use crate::util::vec2::PixelVec;
+use bytemuck::{Pod, Zeroable};
+use wgpu::include_wgsl;
+
+pub struct Pipeline {
+    quad: quad::Pipeline,
+}
+
+impl Pipeline {
+    pub fn new(device: &wgpu::Device) -> Self {
+        Self {
+            quad: quad::Pipeline::new::<PushConstants>(
+                device,
+                quad::Descriptor {
+                    label: "Shadow",
+                    shader: include_wgsl!("shadow.wgsl"),
+                    push_constant_stages: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                    instanced: false,
+                    depth: true,
+                },
+            ),
+        }
+    }
+
+    /// Draw a shadow for a window covering the given rectangle in pixels
+    #[allow(clippy::too_many_arguments)]
+    pub fn render(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        color_target: &wgpu::TextureView,
+        depth_target: &wgpu::TextureView,
+        target_size: PixelVec<u32>,
+        position: PixelVec<f32>,
+        size: PixelVec<f32>,
+        z: f32,
+        color: [f32; 4],
+        corner_radius: f32,
+        blur: f32,
+    ) {
+        let push_constants = PushConstants {
+            target_size: target_size.cast_as(),
+            position,
+            size,
+            padding: [0.; 2],
+            color,
+            corner_radius,
+            blur,
+            z,
+            padding_end: 0.,
+        };
+        self.quad
+            .render(encoder, color_target, Some(depth_target), push_constants);
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, Pod, Zeroable)]
+struct PushConstants {
+    target_size: PixelVec<f32>,
+    position: PixelVec<f32>,
+    size: PixelVec<f32>,
+    padding: [f32; 2],
+    color: [f32; 4],
+    corner_radius: f32,
+    blur: f32,
+    z: f32,
+    padding_end: f32,
+}
@@ -0,0 +1,51 @@
+struct PushConstants {
+    target_size: vec2<f32>,
+    position: vec2<f32>,
+    size: vec2<f32>,
+    color: vec4<f32>,
+    corner_radius: f32,
+    blur: f32,
+    z: f32,
+}
+
+var<push_constant> push_constants: PushConstants;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    // Pixels from the center of the shadow
+    @location(0) local: vec2<f32>,
+}
+
+@vertex
+fn vs_main(
+    @builtin(vertex_index) in_vertex_index: u32,
+) -> VertexOutput {
+    let corner = vec2<f32>(
+        f32(in_vertex_index % 2u),
+        f32(((in_vertex_index + 5u) % 6u) / 3u),
+    );
+    // Leave room for the blur on every side
+    let blur = push_constants.blur;
+    let size = push_constants.size + blur * 2.0;
+    let position = push_constants.position - blur + corner * size;
+
+    var out: VertexOutput;
+    out.local = (corner - 0.5) * size;
+    out.clip_position = vec4<f32>(
+        position / push_constants.target_size * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0),
+        push_constants.z,
+        1.0,
+    );
+    return out;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let half_size = push_constants.size / 2.0;
+    let radius = min(push_constants.corner_radius, min(half_size.x, half_size.y));
+    let q = abs(in.local) - half_size + radius;
+    let distance = length(max(q, vec2<f32>(0.0))) + min(max(q.x, q.y), 0.0) - radius;
+    let blur = push_constants.blur;
+    let alpha = 1.0 - smoothstep(-blur / 2.0, blur, distance);
+    return vec4<f32>(push_constants.color.rgb, push_constants.color.a * alpha);
+}
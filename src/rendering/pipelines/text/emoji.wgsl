@@ -18,6 +18,7 @@ struct PushConstants {
     offset: vec2<i32>,
     z: f32,
     atlas_size: i32,
+    dim: f32,
 }
 
 var<push_constant> constants: PushConstants;
@@ -34,6 +35,7 @@ var<storage, read> cells: array<EmojiCell>;
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
     @location(1) tex_coord: vec2<f32>,
+    @location(2) dim: f32,
 }
 
 
@@ -56,6 +58,7 @@ fn vs_main(
 
     var out: VertexOutput;
     out.tex_coord = origin_uv + size_uv * corner;
+    out.dim = constants.dim;
     out.clip_position = vec4<f32>(
         (
             vec2<f32>(position + constants.offset + glyph_info.offset) + 
@@ -78,6 +81,7 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     );
     let g = 1.0 / 2.2;
     let gamma = vec4<f32>(g, g, g, 1.0);
-    return pow(sample, gamma);
+    let color = pow(sample, gamma);
+    return vec4<f32>(dim(color.rgb, in.dim), color.a);
 }
 
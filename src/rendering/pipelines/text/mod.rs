@@ -1,5 +1,6 @@
 //! Paints monochrome and emoji glyphs with the appropriate highlight colors.
 
+use super::with_dim;
 use crate::{
     rendering::{
         glyph_bind_group::GlyphBindGroup,
@@ -10,7 +11,6 @@ use crate::{
     text::cache::Cached,
     util::vec2::{PixelVec, Vec2},
 };
-use wgpu::include_wgsl;
 
 pub enum Kind {
     Monochrome,
@@ -31,8 +31,8 @@ impl Pipeline {
         kind: Kind,
     ) -> Self {
         let shader = match kind {
-            Kind::Monochrome => include_wgsl!("monochrome.wgsl"),
-            Kind::Emoji => include_wgsl!("emoji.wgsl"),
+            Kind::Monochrome => with_dim("monochrome.wgsl", include_str!("monochrome.wgsl")),
+            Kind::Emoji => with_dim("emoji.wgsl", include_str!("emoji.wgsl")),
         };
         let bind_group = GlyphBindGroup::new(device);
         let shader = device.create_shader_module(shader);
@@ -169,6 +169,8 @@ impl Pipeline {
                         offset: offset + scroll_offset,
                         z,
                         atlas_size: self.atlas_size.try_into().unwrap(),
+                        dim: grid.dim(),
+                        padding: 0,
                     }
                     .set(&mut render_pass);
                     render_pass.draw(0..count * 6, 0..1);
@@ -18,6 +18,7 @@ struct PushConstants {
     offset: vec2<i32>,
     z: f32,
     atlas_size: i32,
+    dim: f32,
 }
 
 var<push_constant> constants: PushConstants;
@@ -64,7 +65,7 @@ fn vs_main(
         constants.z, 
         1.0
     );
-    out.fg = vec3<f32>(grid_cell.r, grid_cell.g, grid_cell.b);
+    out.fg = dim(vec3<f32>(grid_cell.r, grid_cell.g, grid_cell.b), constants.dim);
     return out;
 }
 
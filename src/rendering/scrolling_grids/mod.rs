@@ -1,17 +1,22 @@
 mod range;
 
 use crate::{
-    rendering::Motion,
+    rendering::{animation::Easing, Motion},
     ui::grid::{CellContents, GridContents},
-    util::{nice_s_curve, vec2::CellVec},
+    util::vec2::CellVec,
 };
 use range::Range;
-use std::{collections::VecDeque, time::Duration};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
 
 pub struct ScrollingGrids {
     scrolling: VecDeque<GridPart>,
     t: Duration,
     offset_start: f32,
+    easing: Easing,
+    tracking: Option<Tracking>,
 }
 
 impl ScrollingGrids {
@@ -23,6 +28,8 @@ impl ScrollingGrids {
             scrolling,
             t: Duration::ZERO,
             offset_start: 0.,
+            easing: Easing::default(),
+            tracking: None,
         }
     }
 
@@ -31,7 +38,19 @@ impl ScrollingGrids {
         assert_eq!(self.scrolling.len(), 1);
     }
 
-    pub fn advance(&mut self, delta_time: Duration, speed: f32) -> Motion {
+    pub fn advance(&mut self, delta_time: Duration, speed: f32, easing: Easing) -> Motion {
+        self.easing = easing;
+        if let Some(tracking) = &mut self.tracking {
+            if !tracking.momentum {
+                return Motion::Still;
+            }
+            let max = self.scrolling.front().unwrap().grid.size.0.y as f32;
+            tracking.coast(delta_time, max);
+            // Keep animating until request_lines hands the rest of the motion
+            // to the regular scroll animation
+            return Motion::Animating;
+        }
+
         self.t +=
             Duration::try_from_secs_f32(delta_time.as_secs_f32() * speed).unwrap_or(Duration::ZERO);
         if self.offset_y() == 0. {
@@ -48,8 +67,13 @@ impl ScrollingGrids {
         let mag = offset.abs().min(grid.size.0.y.into());
         let offset = mag * sign;
         let mut coverage = Range::until(grid.size.0.y.into());
-        self.offset_start = self.offset_y() + offset as f32;
-        self.t = Duration::ZERO;
+        match &mut self.tracking {
+            Some(tracking) => tracking.reconcile(offset),
+            None => {
+                self.offset_start = self.offset_y() + offset as f32;
+                self.t = Duration::ZERO;
+            }
+        }
         self.scrolling.retain_mut(|part| {
             part.offset -= offset;
             let grid_range = Range::until(part.grid.size.0.y.into()) + part.offset;
@@ -90,12 +114,160 @@ impl ScrollingGrids {
         self.scrolling.back().unwrap().grid.size
     }
 
+    /// Move the content by the given number of cells, following a trackpad
+    /// rather than animating
+    pub fn track(&mut self, cells: f32, time: Instant) {
+        let max = self.size().0.y as f32;
+        let tracking = match &mut self.tracking {
+            Some(tracking) => tracking,
+            None => {
+                // Pick up from wherever the scroll animation is
+                let offset = self.offset_y();
+                self.offset_start = 0.;
+                self.t = Duration::ZERO;
+                self.tracking.insert(Tracking::new(offset))
+            }
+        };
+        tracking.track(cells, time, max);
+    }
+
+    /// Let tracked scrolling coast to a stop after the fingers lift
+    pub fn release(&mut self, time: Instant) {
+        if let Some(tracking) = &mut self.tracking {
+            tracking.release(time);
+        }
+    }
+
+    /// Take the number of lines Neovim should scroll to keep up with tracked
+    /// scrolling. Positive values scroll down.
+    ///
+    /// Lines are requested one ahead of the content so that the rows coming
+    /// into view are already there. Neovim scrolls by less at the start or end
+    /// of the buffer, so the tracked offset is corrected by however far it
+    /// actually scrolls.
+    pub fn request_lines(&mut self) -> i32 {
+        let Some(tracking) = &mut self.tracking else {
+            return 0;
+        };
+        let expected = tracking.offset + tracking.requested as f32;
+        if tracking.is_stopped() {
+            // Come to rest on the nearest line and let the scroll animation
+            // take care of the rest
+            let offset = tracking.offset;
+            self.offset_start = self.offset_y() + offset;
+            self.t = Duration::ZERO;
+            self.tracking = None;
+            return -expected.round() as i32;
+        }
+        if expected * tracking.direction <= 0. {
+            return 0;
+        }
+        let lines = -(expected.abs().ceil() * expected.signum()) as i32;
+        tracking.requested += lines;
+        lines
+    }
+
     fn offset_y(&self) -> f32 {
-        self.offset_start * (1. - nice_s_curve(self.t.as_secs_f32(), self.offset_start.abs()))
+        let progress = self
+            .easing
+            .ease(self.t.as_secs_f32(), self.offset_start.abs());
+        self.offset_start * (1. - progress)
     }
 
     pub fn offset(&self) -> CellVec<f32> {
-        CellVec::new(0., self.offset_y())
+        let tracked = self.tracking.map_or(0., |tracking| tracking.offset);
+        CellVec::new(0., self.offset_y() + tracked)
+    }
+}
+
+/// Scrolling that follows trackpad pixel deltas directly rather than
+/// animating toward whole lines
+#[derive(Debug, Clone, Copy)]
+struct Tracking {
+    /// How far the content is drawn from where Neovim has it, in cells
+    offset: f32,
+    /// Lines requested from Neovim that have not been scrolled yet, as a
+    /// scroll delta
+    requested: i32,
+    /// The sign of the most recent movement
+    direction: f32,
+    /// The speed of the content in cells per second
+    velocity: f32,
+    last_moved: Option<Instant>,
+    /// Whether the fingers have lifted and the content is coasting
+    momentum: bool,
+}
+
+impl Tracking {
+    /// How quickly coasting slows down, as the fraction of velocity lost per
+    /// second on a log scale
+    const FRICTION: f32 = 4.;
+    /// The speed in cells per second below which coasting stops
+    const MIN_VELOCITY: f32 = 1.;
+    /// The time without movement after which the fingers are considered to
+    /// have stopped before lifting
+    const STILL_TIME: Duration = Duration::from_millis(50);
+
+    fn new(offset: f32) -> Self {
+        Self {
+            offset,
+            requested: 0,
+            direction: 0.,
+            velocity: 0.,
+            last_moved: None,
+            momentum: false,
+        }
+    }
+
+    fn track(&mut self, cells: f32, time: Instant, max: f32) {
+        self.momentum = false;
+        if cells == 0. {
+            return;
+        }
+        let velocity = match self.last_moved {
+            Some(last_moved) if time.duration_since(last_moved) < Self::STILL_TIME * 2 => {
+                let dt = time.duration_since(last_moved).as_secs_f32().max(0.001);
+                // Smooth out the jitter between events
+                (self.velocity + cells / dt) / 2.
+            }
+            _ => 0.,
+        };
+        self.velocity = velocity;
+        self.last_moved = Some(time);
+        self.direction = cells.signum();
+        self.offset = (self.offset + cells).clamp(-max, max);
+    }
+
+    fn release(&mut self, time: Instant) {
+        self.momentum = true;
+        let still = match self.last_moved {
+            Some(last_moved) => time.duration_since(last_moved) > Self::STILL_TIME,
+            None => true,
+        };
+        if still {
+            self.velocity = 0.;
+        }
+    }
+
+    fn coast(&mut self, delta_time: Duration, max: f32) {
+        let dt = delta_time.as_secs_f32();
+        self.offset = (self.offset + self.velocity * dt).clamp(-max, max);
+        self.velocity *= (-Self::FRICTION * dt).exp();
+        if self.velocity != 0. {
+            self.direction = self.velocity.signum();
+        }
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.momentum && self.velocity.abs() < Self::MIN_VELOCITY
+    }
+
+    /// Account for Neovim scrolling by the given delta
+    fn reconcile(&mut self, delta: i32) {
+        self.offset += delta as f32;
+        if self.requested.signum() == delta.signum() {
+            self.requested -= delta.abs().min(self.requested.abs()) * delta.signum();
+        }
     }
 }
 
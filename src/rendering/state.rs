@@ -1,20 +1,28 @@
 use super::{
-    cmdline_grid::CmdlineGrid, grids::Grids, message_grids::MessageGrids, pipelines::Pipelines,
-    targets::Targets, text::BindGroupLayout as TextBindGroup, wgpu_context::WgpuContext, Motion,
+    cmdline_grid::CmdlineGrid,
+    grids::Grids,
+    layout::Layout,
+    message_grids::MessageGrids,
+    pipelines::{overlay::Rect, post_process, Pipelines},
+    targets::Targets,
+    text::BindGroupLayout as TextBindGroup,
+    wgpu_context::WgpuContext,
+    Motion,
 };
 use crate::{
     event::rgb::Rgb,
     event_handler::settings::Settings,
     text::{cache::FontCache, fonts::Fonts},
-    ui::Ui,
+    ui::{grid, Ui},
     util::vec2::{PixelVec, Vec2},
 };
 use bytemuck::cast_slice;
 use std::{
     fs::File,
     io::{self, BufWriter},
+    path::PathBuf,
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant},
 };
 use swash::shape::ShapeContext;
 use winit::window::Window;
@@ -117,14 +125,14 @@ impl RenderState {
         );
     }
 
-    pub fn resize(&mut self, new_size: PixelVec<u32>, cell_size: Vec2<u32>, transparent: bool) {
+    pub fn resize(&mut self, new_size: PixelVec<u32>, layout: Layout, transparent: bool) {
         if new_size == PixelVec::default() {
             return;
         }
 
         self.wgpu_context.resize(new_size);
 
-        let target_size: PixelVec<u32> = (new_size.into_cells(cell_size)).into_pixels(cell_size);
+        let target_size = layout.size;
         self.targets = Targets::new(&self.wgpu_context.device, target_size);
 
         self.pipelines.gamma_blit_final.update(
@@ -133,6 +141,7 @@ impl RenderState {
             &self.targets.color.view,
             target_size,
             new_size,
+            layout.offset,
             transparent,
         );
         self.pipelines.blit_png.update(
@@ -140,6 +149,14 @@ impl RenderState {
             &self.targets.color.view,
             self.targets.png_size.0.x as f32 / target_size.0.x as f32,
         );
+        self.pipelines.layer.update(
+            &self.wgpu_context.device,
+            &self.targets.layer.view,
+            &self.targets.layer_monochrome.view,
+        );
+        self.pipelines
+            .post_process
+            .update(&self.wgpu_context.device, &self.targets.color.view);
     }
 
     pub fn advance(
@@ -151,25 +168,50 @@ impl RenderState {
         let mut motion = Motion::Still;
 
         for grid in self.grids.iter_mut() {
-            motion = motion.soonest(
-                grid.scrolling
-                    .advance(delta_time, settings.scroll_speed * cell_size.y),
-            );
+            motion = motion.soonest(grid.scrolling.advance(
+                delta_time,
+                settings.scroll_speed * cell_size.y,
+                settings.easing.scroll,
+            ));
         }
 
         const DEFAULT_CURSOR_SPEED: f32 = 100.;
         let cursor_speed = settings.cursor_speed * DEFAULT_CURSOR_SPEED;
-        motion = motion.soonest(
-            self.pipelines
-                .cursor
-                .advance(delta_time, cursor_speed, cell_size),
-        );
+        // Windows move like the cursor does
+        motion = motion.soonest(self.grids.advance(
+            delta_time,
+            cursor_speed,
+            settings.easing.cursor,
+            settings,
+            cell_size,
+        ));
+        motion = motion.soonest(self.pipelines.cursor.advance(
+            delta_time,
+            cursor_speed,
+            settings.easing.cursor,
+            cell_size,
+        ));
         motion = motion.soonest(self.pipelines.cmdline_cursor.advance(
             delta_time,
             cursor_speed,
+            settings.easing.cmdline_cursor,
+            cell_size,
+        ));
+        motion = motion.soonest(self.pipelines.cursor_effects.advance(
+            &self.wgpu_context.device,
+            &self.wgpu_context.queue,
+            delta_time,
+            &self.pipelines.cursor,
+            settings.cursor_effects,
             cell_size,
         ));
 
+        // Changes to the shader file are watched for separately, so frames
+        // are only needed here for shaders that animate over time
+        if settings.animate_post_process && self.pipelines.post_process.is_active() {
+            motion = motion.soonest(Motion::Animating);
+        }
+
         motion
     }
 
@@ -190,7 +232,9 @@ impl RenderState {
                 match e {
                     wgpu::SurfaceError::Lost => {
                         log::warn!("Rebuilding swap chain");
-                        self.resize(self.surface_size(), cell_size, settings.transparent);
+                        let surface_size = self.surface_size();
+                        let layout = Layout::new(surface_size, cell_size, settings.layout);
+                        self.resize(surface_size, layout, settings.transparent);
                     }
                     _ => log::error!("{e}"),
                 }
@@ -210,12 +254,14 @@ impl RenderState {
         let target_size = self.targets.color.texture.size().into();
 
         let grid_count = self.grids.grid_count() as f32;
+        let depth = |i: usize| (i as f32 + 1.) / (grid_count + 1.);
         let grids = || {
             self.grids
                 .front_to_back()
+                .filter(|(_, grid)| !grid.is_layered())
                 .map(|(z, grid)| {
                     (
-                        (z as f32 + 1.) / (grid_count + 1.),
+                        depth(z),
                         grid.scrolling.offset().round_to_pixels(cell_size),
                         &grid.text,
                     )
@@ -274,6 +320,116 @@ impl RenderState {
             .blend
             .render(&mut encoder, &self.targets.color.view);
 
+        // Grids that are fading, scaling, or styled are drawn on their own and
+        // then composited over the grids below them
+        let layers: Vec<_> = self
+            .grids
+            .front_to_back()
+            .filter(|(_, grid)| grid.is_layered())
+            .collect();
+        for &(z, grid) in layers.iter().rev() {
+            let Some(offset) = grid.text.offset() else {
+                continue;
+            };
+            let z = depth(z);
+            let layer = || {
+                std::iter::once((
+                    z,
+                    grid.scrolling.offset().round_to_pixels(cell_size),
+                    &grid.text,
+                ))
+            };
+
+            self.pipelines.default_fill.render(
+                &mut encoder,
+                layer().map(|(z, _, grid)| (z, grid)),
+                &self.targets.layer.view,
+                &self.targets.layer_depth.view,
+                target_size,
+                cell_size,
+                self.clear_color,
+            );
+
+            self.pipelines.cell_fill.render(
+                &mut encoder,
+                layer(),
+                &self.targets.layer.view,
+                &self.targets.layer_depth.view,
+                target_size,
+                cell_size,
+            );
+
+            self.pipelines.monochrome.render(
+                &mut encoder,
+                layer(),
+                &self.targets.layer_monochrome.view,
+                &self.targets.layer_depth.view,
+                target_size,
+                cell_size,
+            );
+
+            self.pipelines.lines.render(
+                &mut encoder,
+                layer(),
+                &self.targets.layer_monochrome.view,
+                &self.targets.layer_depth.view,
+                target_size,
+                cell_size,
+                settings.underline_offset,
+            );
+
+            self.pipelines
+                .blend
+                .render_layer(&mut encoder, &self.targets.layer.view);
+
+            self.pipelines.emoji.render(
+                &mut encoder,
+                layer(),
+                &self.targets.layer.view,
+                &self.targets.layer_depth.view,
+                target_size,
+                cell_size,
+            );
+
+            let position = offset.round_to_pixels(cell_size);
+            let size = grid.text.size().into_pixels(cell_size);
+            let style = grid.style();
+            if let Some(shadow) = style.shadow {
+                let scale = grid.scale();
+                let scaled_size = size.cast_as::<f32>() * scale;
+                let scaled_position = position.cast_as::<f32>()
+                    + (size.cast_as::<f32>() - scaled_size) / 2.
+                    + shadow.offset;
+                self.pipelines.shadow.render(
+                    &mut encoder,
+                    &self.targets.color.view,
+                    &self.targets.depth.view,
+                    target_size,
+                    scaled_position,
+                    scaled_size,
+                    // Just behind the grid and in front of the grid below it
+                    z + 0.5 / (grid_count + 1.),
+                    shadow.color.into_srgb(shadow.opacity * grid.opacity()),
+                    style.corner_radius * scale,
+                    shadow.blur * scale,
+                );
+            }
+
+            self.pipelines.layer.render(
+                &mut encoder,
+                &self.targets.color.view,
+                &self.targets.monochrome.view,
+                &self.targets.depth.view,
+                target_size,
+                position,
+                size,
+                z,
+                grid.opacity(),
+                grid.scale(),
+                style.corner_radius,
+            );
+        }
+
         self.pipelines.cursor.render(
             &mut encoder,
             &self.targets.color.view,
@@ -286,6 +442,10 @@ impl RenderState {
             target_size.cast_as(),
         );
 
+        self.pipelines
+            .cursor_effects
+            .render(&mut encoder, &self.targets.color.view, target_size);
+
         self.pipelines.emoji.render(
             &mut encoder,
             grids(),
@@ -295,14 +455,32 @@ impl RenderState {
             cell_size,
         );
 
+        let cursor = self
+            .pipelines
+            .cursor
+            .rect()
+            .map(|(position, size)| position + PixelVec(size / 2.));
+        self.pipelines
+            .post_process
+            .render(&mut encoder, &self.targets, target_size, cursor);
+
+        self.pipelines
+            .overlay
+            .render(&mut encoder, &self.targets.color.view, target_size);
+
+        // The space around the grid
+        let fill = settings
+            .layout
+            .fill
+            .map_or(self.clear_color, |fill| fill.into_srgb(self.clear_color[3]));
         self.pipelines.gamma_blit_final.render(
             &mut encoder,
             &output_view,
             wgpu::Color {
-                r: (self.clear_color[0] as f64).powf(2.2),
-                g: (self.clear_color[1] as f64).powf(2.2),
-                b: (self.clear_color[2] as f64).powf(2.2),
-                a: (self.clear_color[3] as f64).powf(2.2),
+                r: (fill[0] as f64).powf(2.2),
+                g: (fill[1] as f64).powf(2.2),
+                b: (fill[2] as f64).powf(2.2),
+                a: (fill[3] as f64).powf(2.2),
             },
         );
 
@@ -378,6 +556,58 @@ impl RenderState {
         output.present();
     }
 
+    /// Move the grid contents by the given number of cells to follow a
+    /// trackpad
+    pub fn track_scroll(&mut self, grid: grid::Id, cells: f32) {
+        if let Some(grid) = self.grids.get_mut(grid) {
+            grid.scrolling.track(cells, Instant::now());
+        }
+    }
+
+    /// Let trackpad scrolling coast to a stop
+    pub fn release_scroll(&mut self) {
+        let now = Instant::now();
+        for grid in self.grids.iter_mut() {
+            grid.scrolling.release(now);
+        }
+    }
+
+    /// Take the lines each grid needs Neovim to scroll to keep up with
+    /// trackpad scrolling. Positive values scroll down.
+    pub fn scroll_requests(&mut self) -> Vec<(grid::Id, i32)> {
+        self.grids.scroll_requests()
+    }
+
+    /// Set the rectangles to paint over the frame
+    pub fn set_overlay(&mut self, rects: &[Rect]) {
+        self.pipelines
+            .overlay
+            .update(&self.wgpu_context.device, &self.wgpu_context.queue, rects);
+    }
+
+    /// Run the WGSL shader at the given path over each frame, or stop
+    /// post-processing if None
+    pub fn set_post_process(&mut self, path: Option<PathBuf>) -> Result<(), post_process::Error> {
+        match path {
+            Some(path) => self
+                .pipelines
+                .post_process
+                .load(&self.wgpu_context.device, path),
+            None => {
+                self.pipelines.post_process.unload();
+                Ok(())
+            }
+        }
+    }
+
+    /// Recompile the post-process shader if its file has changed. Returns
+    /// whether it was reloaded.
+    pub fn reload_post_process(&mut self) -> Result<bool, post_process::Error> {
+        self.pipelines
+            .post_process
+            .reload(&self.wgpu_context.device)
+    }
+
     pub fn clear_glyph_cache(&mut self) {
         self.font_cache.clear();
         self.pipelines.emoji.clear();
@@ -5,6 +5,14 @@ pub struct Targets {
     pub monochrome: Texture,
     pub color: Texture,
     pub depth: Texture,
+    /// Targets for drawing a grid on its own before it is composited onto
+    /// the color target
+    pub layer: Texture,
+    pub layer_monochrome: Texture,
+    pub layer_depth: Texture,
+    /// Where a post-process shader draws to before the result is copied back
+    /// to the color target
+    pub post: Texture,
     pub png: Texture,
     pub png_staging: wgpu::Buffer,
     pub png_size: PixelVec<u32>,
@@ -29,7 +37,7 @@ impl Targets {
                     "Monochrome texture",
                     size.into(),
                     Texture::LINEAR_FORMAT,
-                    Texture::ATTACHMENT_AND_BINDING,
+                    Texture::ATTACHMENT_AND_BINDING | wgpu::TextureUsages::COPY_DST,
                 ),
             ),
             depth: Texture::target(
@@ -41,6 +49,42 @@ impl Targets {
                     wgpu::TextureUsages::RENDER_ATTACHMENT,
                 ),
             ),
+            layer: Texture::target(
+                device,
+                &Texture::descriptor(
+                    "Layer texture",
+                    size.into(),
+                    Texture::LINEAR_FORMAT,
+                    Texture::ATTACHMENT_AND_BINDING,
+                ),
+            ),
+            layer_monochrome: Texture::target(
+                device,
+                &Texture::descriptor(
+                    "Layer monochrome texture",
+                    size.into(),
+                    Texture::LINEAR_FORMAT,
+                    Texture::ATTACHMENT_AND_BINDING,
+                ),
+            ),
+            layer_depth: Texture::target(
+                device,
+                &Texture::descriptor(
+                    "Layer depth texture",
+                    size.into(),
+                    Texture::DEPTH_FORMAT,
+                    wgpu::TextureUsages::RENDER_ATTACHMENT,
+                ),
+            ),
+            post: Texture::target(
+                device,
+                &Texture::descriptor(
+                    "Post-process texture",
+                    size.into(),
+                    Texture::LINEAR_FORMAT,
+                    Texture::ATTACHMENT_AND_BINDING | wgpu::TextureUsages::COPY_SRC,
+                ),
+            ),
             png: Texture::target(
                 device,
                 &Texture::descriptor(
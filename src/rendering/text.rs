@@ -2,9 +2,10 @@ use crate::{
     event::{hl_attr_define::Attributes, rgb::Rgb},
     text::{
         cache::{CacheValue, FontCache, GlyphKind},
+        font::Metrics,
         fonts::{FontStyle, Fonts},
     },
-    ui::grid::CellContents,
+    ui::grid::{CellContents, GridContents},
     util::vec2::{CellVec, PixelVec, Vec2},
 };
 use bytemuck::{cast_slice, Pod, Zeroable};
@@ -30,6 +31,10 @@ pub struct Text {
     lines_bind_group: Option<wgpu::BindGroup>,
     window_position: Option<CellVec<f32>>,
     size: CellVec<u32>,
+    /// Whether any cell background is see-through, as with winblend
+    translucent: bool,
+    /// How much to darken and desaturate the grid, from 0 to 1
+    dim: f32,
 }
 
 impl Text {
@@ -49,6 +54,8 @@ impl Text {
             // causing the initial Telescope scroll.
             window_position: None,
             size,
+            translucent: false,
+            dim: 0.,
         }
     }
 
@@ -70,6 +77,9 @@ impl Text {
         let metrics = fonts.metrics();
         let metrics_px = metrics.into_pixels();
         let cell_size = metrics_px.cell_size();
+        // Keeps underlines in place relative to the glyphs when the line
+        // height moves the baseline away from the em box
+        let baseline_adjustment = metrics_px.baseline as i32 - metrics_px.em as i32;
 
         let default_fg = default_fg.into_srgb(1.);
         let default_bg = default_bg.into_srgb(1.);
@@ -86,13 +96,25 @@ impl Text {
             self.size.0.x = self.size.0.x.max(line_length);
             self.size.0.y += 1;
             line_length = 0;
+            let mut cells = cell_line.enumerate().peekable();
             let mut parser = Parser::new(
                 Script::Latin,
-                cell_line.enumerate().flat_map(|(cell_i, cell)| {
+                std::iter::from_fn(move || {
+                    let (cell_i, cell) = cells.next()?;
+                    // Neovim follows a double-width character with an empty
+                    // cell, which we fold into the character's token
+                    let wide =
+                        GridContents::is_wide_cell(&cell, cells.peek().map(|(_, next)| next));
+                    if wide {
+                        cells.next();
+                    }
+                    Some((cell_i, cell, if wide { 2 } else { 1 }))
+                })
+                .flat_map(|(cell_i, cell, width)| {
                     cell.text.map(move |c| Token {
                         ch: c,
                         offset: cell_i as u32,
-                        len: 1,
+                        len: width,
                         info: c.into(),
                         data: cell.highlight,
                     })
@@ -103,8 +125,11 @@ impl Text {
             let mut is_parser_empty = false;
             while !is_parser_empty {
                 if let Some(current_font_unwrapped) = next_font {
-                    let font_info = fonts.families().nth(current_font_unwrapped.index).unwrap();
+                    let font_info = fonts.family(current_font_unwrapped.index).unwrap();
                     let font = font_info.style(current_font_unwrapped.style).unwrap();
+                    let wide_layout = fonts
+                        .is_wide(current_font_unwrapped.index)
+                        .then(|| WideLayout::new(font.metrics(), metrics, cell_size));
                     let mut shaper = shape_context
                         .builder(font.as_ref())
                         .features(font_info.setting.features.iter().cloned())
@@ -138,7 +163,7 @@ impl Text {
                     }
 
                     shaper.shape_with(|cluster| {
-                        line_length += 1;
+                        line_length += cluster.source.end - cluster.source.start;
                         let (fg, is_underlined) = if let Some(hl) = highlights
                             .get(cluster.data as usize)
                             .and_then(|hl| (*hl).as_ref())
@@ -162,16 +187,20 @@ impl Text {
                                 // cell fill characters during shaping without worrying
                                 // too much about whether a glyph cluster spans multiple
                                 // cells. This is something to improve on in the future
-                                // in case some fonts contain actual ligatures.
-                                let bg_cell = BgCell {
-                                    x: cluster.source.start.try_into().unwrap(),
-                                    y: cell_line_i,
-                                    r: bg[0],
-                                    g: bg[1],
-                                    b: bg[2],
-                                    a: bg[3],
-                                };
-                                self.cell_fill.push(bg_cell);
+                                // in case some fonts contain actual ligatures. The
+                                // exception is double-width characters, whose cluster
+                                // covers both of their cells.
+                                for i in cluster.source.start..cluster.source.end {
+                                    let bg_cell = BgCell {
+                                        x: i.try_into().unwrap(),
+                                        y: cell_line_i,
+                                        r: bg[0],
+                                        g: bg[1],
+                                        b: bg[2],
+                                        a: bg[3],
+                                    };
+                                    self.cell_fill.push(bg_cell);
+                                }
                             }
 
                             (
@@ -187,12 +216,33 @@ impl Text {
                         };
 
                         let x = cluster.source.start * cell_size.x;
-                        let mut advanced = 0.0f32;
+                        let width = (cluster.source.end - cluster.source.start) * cell_size.x;
+                        let (scale_factor, glyph_size, mut advanced, baseline) = match wide_layout {
+                            Some(layout) => {
+                                let width: f32 = cluster
+                                    .glyphs
+                                    .iter()
+                                    .map(|glyph| glyph.advance * layout.scale_factor)
+                                    .sum();
+                                (
+                                    layout.scale_factor,
+                                    layout.size,
+                                    (cell_size.x as f32 * 2. - width) / 2.,
+                                    layout.baseline,
+                                )
+                            }
+                            None => (
+                                metrics.scale_factor,
+                                metrics.em,
+                                metrics_px.glyph_offset() as f32,
+                                metrics_px.baseline as i32,
+                            ),
+                        };
                         for glyph in cluster.glyphs {
                             let CacheValue { index, kind } = match font_cache.get(
                                 font.as_ref(),
-                                font_info.setting.variations.as_slice(),
-                                metrics.em,
+                                &font_info.setting,
+                                glyph_size,
                                 glyph.id,
                                 current_font_unwrapped.style,
                                 current_font_unwrapped.index,
@@ -204,22 +254,26 @@ impl Text {
                             };
                             let glyph_index = index as u32;
 
-                            let position = Vec2::new(glyph.x, glyph.y) * metrics.scale_factor;
+                            let position = Vec2::new(glyph.x, glyph.y) * scale_factor;
                             let position = Vec2::new(
                                 position.x.round() as i32 + x as i32 + advanced.round() as i32,
-                                position.y.round() as i32 + (cell_line_i * cell_size.y as i32),
+                                position.y.round() as i32 + (cell_line_i * cell_size.y as i32)
+                                    - font_info.setting.baseline_offset.round() as i32,
                             );
 
                             if is_underlined {
                                 let line_position = position
                                     + Vec2::new(
                                         0,
-                                        (metrics_px.ascent + metrics_px.underline_offset) as i32,
+                                        (metrics_px.ascent + metrics_px.underline_offset) as i32
+                                            + baseline_adjustment,
                                     );
-                                let line_size =
-                                    Vec2::new(metrics_px.width, metrics_px.stroke_size.max(1));
+                                // Span the whole cell, or both cells of a
+                                // double-width character, so that letter spacing
+                                // doesn't leave gaps in the line
+                                let line_size = Vec2::new(width, metrics_px.stroke_size.max(1));
                                 self.decoration.push(Decoration {
-                                    x: line_position.x,
+                                    x: x as i32,
                                     y: line_position.y,
                                     w: line_size.x,
                                     h: line_size.y,
@@ -229,7 +283,7 @@ impl Text {
                                 })
                             }
 
-                            let position = position + Vec2::new(0, metrics_px.em as i32);
+                            let position = position + Vec2::new(0, baseline);
                             match kind {
                                 GlyphKind::Monochrome => self.monochrome.push(MonochromeCell {
                                     x: position.x,
@@ -245,7 +299,7 @@ impl Text {
                                     glyph_index: glyph_index.try_into().unwrap(),
                                 }),
                             }
-                            advanced += glyph.advance * metrics.scale_factor;
+                            advanced += glyph.advance * scale_factor;
                         }
                     });
                 } else {
@@ -263,7 +317,7 @@ impl Text {
                                 let bg = bg.into_srgb(hl.blend());
                                 for i in range.start..range.end {
                                     let bg_cell = BgCell {
-                                        x: (i * cell_size.x).try_into().unwrap(),
+                                        x: i.try_into().unwrap(),
                                         y: cell_line_i,
                                         r: bg[0],
                                         g: bg[1],
@@ -293,6 +347,7 @@ impl Text {
         if let Some(size) = size {
             self.size = size;
         }
+        self.translucent = self.cell_fill.iter().any(|cell| cell.a < 1.);
 
         let glyphs = cast_slice(self.monochrome.as_slice());
         let emoji = cast_slice(self.emoji.as_slice());
@@ -405,6 +460,14 @@ impl Text {
         self.window_position = window_position;
     }
 
+    pub fn update_dim(&mut self, dim: f32) {
+        self.dim = dim;
+    }
+
+    pub fn dim(&self) -> f32 {
+        self.dim
+    }
+
     pub fn cell_fill_bind_group(&self) -> Option<&wgpu::BindGroup> {
         self.cell_fill_bind_group.as_ref()
     }
@@ -425,6 +488,11 @@ impl Text {
         self.size
     }
 
+    /// Whether any cell background lets the grids below show through
+    pub fn is_translucent(&self) -> bool {
+        self.translucent
+    }
+
     pub fn offset(&self) -> Option<CellVec<f32>> {
         self.window_position
     }
@@ -481,8 +549,11 @@ fn best_font(
         .and_then(|hl| (*hl).as_ref())
         .map(|highlight| FontStyle::new(highlight.bold(), highlight.italic()))
         .unwrap_or_default();
+    let range = cluster.range();
+    let is_wide = range.end - range.start > 1;
+    let wide_families = fonts.wide_families().filter(|_| is_wide);
     let mut best_font = None;
-    for (i, font_info) in fonts.families().enumerate() {
+    for (i, font_info) in wide_families.chain(fonts.families().enumerate()) {
         if let Some(font) = &font_info.style(style) {
             match cluster.map(|c| font.charmap().map(c)) {
                 Status::Discard => {}
@@ -513,6 +584,35 @@ fn best_font(
     best_font
 }
 
+/// Placement for glyphs from a guifontwide family, which are scaled down to
+/// fit within two cells if needed and centered within them
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct WideLayout {
+    /// Multiplier from font units to pixels
+    scale_factor: f32,
+    /// The font size to rasterize glyphs at
+    size: f32,
+    /// Distance from the top of the cell to the baseline in pixels
+    baseline: i32,
+}
+
+impl WideLayout {
+    pub fn new(wide: Metrics, regular: Metrics, cell_size: Vec2<u32>) -> Self {
+        let cell_size = cell_size.cast_as::<f32>();
+        let height = wide.ascent + wide.descent;
+        // Ideographs usually advance by one em
+        let scale = (cell_size.x * 2. / wide.em)
+            .min(cell_size.y / height)
+            .min(1.);
+        let top = (cell_size.y - height * scale) / 2.;
+        Self {
+            scale_factor: wide.scale_factor * scale,
+            size: regular.em * scale,
+            baseline: (top + wide.ascent * scale).round() as i32,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct BestFont {
     index: usize,
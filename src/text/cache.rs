@@ -1,10 +1,13 @@
-use super::{atlas::FontAtlas, fonts::FontStyle};
+use super::{
+    atlas::FontAtlas,
+    fonts::{FontSetting, FontStyle},
+};
 use crate::util::vec2::Vec2;
 use bytemuck::{Pod, Zeroable};
 use std::collections::{hash_map::Entry, HashMap};
 use swash::{
     scale::{image::Content, Render, ScaleContext, Source, StrikeWith},
-    FontRef, GlyphId, Setting,
+    FontRef, GlyphId,
 };
 
 /// A cache of font glyphs
@@ -46,7 +49,7 @@ impl FontCache {
     pub fn get(
         &mut self,
         font: FontRef,
-        variations: &[Setting<f32>],
+        setting: &FontSetting,
         size: f32,
         glyph_id: GlyphId,
         style: FontStyle,
@@ -64,14 +67,15 @@ impl FontCache {
                     .scale_context
                     .builder(font)
                     .size(size)
-                    .hint(true)
-                    .variations(variations.iter().cloned())
+                    .hint(setting.hinting)
+                    .variations(setting.variations.iter().cloned())
                     .build();
                 match Render::new(&[
                     Source::ColorOutline(0),
                     Source::ColorBitmap(StrikeWith::BestFit),
                     Source::Outline,
                 ])
+                .embolden(setting.embolden)
                 .render(&mut scaler, glyph_id)
                 {
                     Some(image) => {
@@ -126,6 +126,12 @@ pub struct Metrics {
     pub strikeout_offset: f32,
     /// Recommended thickness of an underline or strikeout stroke.
     pub stroke_size: f32,
+    /// The height of a cell
+    pub line_height: f32,
+    /// Distance from the top of the cell to the baseline.
+    pub baseline: f32,
+    /// The width of a cell including letter spacing
+    pub cell_width: f32,
 }
 
 impl Metrics {
@@ -134,6 +140,30 @@ impl Metrics {
         self.into()
     }
 
+    /// Scale the line height by the given multiplier, keeping the glyphs
+    /// vertically centered in the cell
+    pub fn with_line_height(self, multiplier: f32) -> Self {
+        let natural = self.em + self.descent;
+        let line_height = (natural * multiplier).max(1.);
+        Self {
+            line_height,
+            baseline: self.em + (line_height - natural) / 2.,
+            ..self
+        }
+    }
+
+    /// Add space between lines and between letters, keeping the glyphs
+    /// centered in the cell
+    pub fn with_spacing(self, linespace: f32, letter_spacing: f32) -> Self {
+        let line_height = (self.line_height + linespace).max(1.);
+        Self {
+            line_height,
+            baseline: self.baseline + (line_height - self.line_height) / 2.,
+            cell_width: (self.cell_width + letter_spacing).max(1.),
+            ..self
+        }
+    }
+
     fn new(font: FontRef, size: FontSize) -> Self {
         let metrics = font.metrics(&[]);
         let (scale_factor, em) = match size {
@@ -162,6 +192,9 @@ impl Metrics {
             underline_offset: metrics.underline_offset,
             strikeout_offset: metrics.strikeout_offset,
             stroke_size: metrics.stroke_size,
+            line_height: em + metrics.descent,
+            baseline: em,
+            cell_width: metrics.max_width,
         }
     }
 }
@@ -194,12 +227,23 @@ pub struct MetricsPixels {
     pub strikeout_offset: u32,
     /// Recommended thickness of an underline or strikeout stroke.
     pub stroke_size: u32,
+    /// The height of a cell
+    pub line_height: u32,
+    /// Distance from the top of the cell to the baseline.
+    pub baseline: u32,
+    /// The width of a cell including letter spacing
+    pub cell_width: u32,
 }
 
 impl MetricsPixels {
     /// The dimensions of a grid cell with this font
     pub fn cell_size(&self) -> Vec2<u32> {
-        Vec2::new(self.width, self.em + self.descent)
+        Vec2::new(self.cell_width, self.line_height)
+    }
+
+    /// Horizontal offset that centers glyphs in a cell with letter spacing
+    pub fn glyph_offset(&self) -> i32 {
+        (self.cell_width as i32 - self.width as i32) / 2
     }
 }
 
@@ -217,6 +261,9 @@ impl From<Metrics> for MetricsPixels {
             underline_offset: metrics.underline_offset.round() as u32,
             strikeout_offset: metrics.strikeout_offset.round() as u32,
             stroke_size: metrics.stroke_size.round() as u32,
+            line_height: metrics.line_height.round() as u32,
+            baseline: metrics.baseline.round() as u32,
+            cell_width: metrics.cell_width.round() as u32,
         }
     }
 }
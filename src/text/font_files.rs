@@ -0,0 +1,221 @@
+//! Loads fonts from file paths and font directories rather than the system
+//! font database
+
+use super::font::{Font, FontFromFileError};
+use crate::ui::options::FontSize;
+use std::{
+    collections::HashMap,
+    env, fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use swash::{FontDataRef, FontRef, StringId};
+
+const EXTENSIONS: [&str; 4] = ["ttf", "otf", "ttc", "otc"];
+
+/// Whether the font name should be treated as a path rather than a family name.
+/// Backslashes only count at the start, as in drive and network paths, since
+/// guifont uses them to escape characters in family names.
+pub fn is_path(name: &str) -> bool {
+    name.contains('/')
+        || name.starts_with('~')
+        || name.starts_with("\\\\")
+        || has_drive(name)
+        || has_font_extension(Path::new(name))
+}
+
+/// Whether the path starts with a Windows drive letter, such as C:\
+pub fn has_drive(path: &str) -> bool {
+    path.starts_with(|c: char| c.is_ascii_alphabetic())
+        && path.get(1..3).is_some_and(|s| s == ":\\" || s == ":/")
+}
+
+/// Replace a leading tilde with the home directory
+pub fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix('~') {
+        Some(rest) => match env::var_os("HOME").or_else(|| env::var_os("USERPROFILE")) {
+            Some(home) => {
+                let rest = rest.trim_start_matches(['/', '\\']);
+                PathBuf::from(home).join(rest)
+            }
+            None => PathBuf::from(path),
+        },
+        None => PathBuf::from(path),
+    }
+}
+
+/// Load the fonts at the given path. For a directory, all the fonts it
+/// contains are loaded. For a file, the file is loaded along with any other
+/// files in the same directory that belong to the same family, such that
+/// pointing at the regular variant also picks up the bold and italic ones.
+pub fn load_path(path: &Path, size: FontSize) -> Result<Vec<Font>, FontFromFileError> {
+    if path.is_dir() {
+        return Ok(font_files(path, false)
+            .into_iter()
+            .flat_map(|path| load_file_logged(&path, size))
+            .collect());
+    }
+
+    let mut fonts = load_file(path, size)?;
+    let names: Vec<_> = fonts
+        .iter()
+        .flat_map(|font| family_names(font.as_ref()))
+        .collect();
+    if let Some(parent) = path.parent() {
+        for sibling in font_files(parent, false) {
+            if sibling == path {
+                continue;
+            }
+            fonts.extend(
+                load_file_logged(&sibling, size)
+                    .into_iter()
+                    .filter(|font| family_names(font.as_ref()).any(|name| names.contains(&name))),
+            );
+        }
+    }
+    Ok(fonts)
+}
+
+/// The fonts in a set of directories and their subdirectories, indexed by
+/// family name so that looking up a family doesn't read every file
+#[derive(Debug, Clone, Default)]
+pub struct FontIndex {
+    dirs: Vec<PathBuf>,
+    /// Lowercase family names to the files and face indices in the family
+    families: HashMap<String, Vec<(PathBuf, usize)>>,
+}
+
+impl FontIndex {
+    pub fn new(dirs: Vec<PathBuf>) -> Self {
+        let mut families: HashMap<String, Vec<(PathBuf, usize)>> = HashMap::new();
+        for path in dirs.iter().flat_map(|dir| font_files(dir, true)) {
+            let data = match fs::read(&path) {
+                Ok(data) => data,
+                Err(e) => {
+                    log::warn!("Failed to read font file {}: {e}", path.display());
+                    continue;
+                }
+            };
+            let Some(count) = FontDataRef::new(&data).map(|data| data.len()) else {
+                log::warn!("Failed to load font file {}", path.display());
+                continue;
+            };
+            for index in 0..count {
+                let Some(font) = FontRef::from_index(&data, index) else {
+                    continue;
+                };
+                for name in family_names(font) {
+                    let faces = families.entry(name).or_default();
+                    // The family and typographic family are often the same
+                    if faces.last() != Some(&(path.clone(), index)) {
+                        faces.push((path.clone(), index));
+                    }
+                }
+            }
+        }
+        Self { dirs, families }
+    }
+
+    /// The directories that were indexed
+    pub fn dirs(&self) -> &[PathBuf] {
+        &self.dirs
+    }
+
+    /// Load the fonts with the given family name
+    pub fn find(&self, name: &str, size: FontSize) -> Vec<Font> {
+        let Some(faces) = self.families.get(&name.to_lowercase()) else {
+            return vec![];
+        };
+        let mut out = vec![];
+        // Faces from the same collection share its data
+        let mut file: Option<(&Path, Arc<Vec<u8>>)> = None;
+        for (path, index) in faces {
+            let data = match &file {
+                Some((loaded, data)) if loaded == path => data.clone(),
+                _ => match fs::read(path) {
+                    Ok(data) => file.insert((path, Arc::new(data))).1.clone(),
+                    Err(e) => {
+                        log::warn!("Failed to read font file {}: {e}", path.display());
+                        continue;
+                    }
+                },
+            };
+            out.extend(Font::from_bytes(data, *index, size));
+        }
+        out
+    }
+}
+
+/// Load every face in the given font file or collection
+pub fn load_file(path: &Path, size: FontSize) -> Result<Vec<Font>, FontFromFileError> {
+    let data = Arc::new(fs::read(path)?);
+    let count = FontDataRef::new(&data)
+        .map(|data| data.len())
+        .ok_or(FontFromFileError::Font)?;
+    (0..count)
+        .map(|index| Font::from_bytes(data.clone(), index, size).ok_or(FontFromFileError::Font))
+        .collect()
+}
+
+fn load_file_logged(path: &Path, size: FontSize) -> Vec<Font> {
+    match load_file(path, size) {
+        Ok(fonts) => fonts,
+        Err(e) => {
+            log::warn!("Failed to load font file {}: {e}", path.display());
+            vec![]
+        }
+    }
+}
+
+/// The lowercase family names of the font. Fonts with many weights often only
+/// give the shared family name as the typographic family.
+fn family_names(font: FontRef<'_>) -> impl Iterator<Item = String> + '_ {
+    let strings = font.localized_strings();
+    [StringId::Family, StringId::TypographicFamily]
+        .into_iter()
+        .filter_map(move |id| strings.find_by_id(id, None))
+        .map(|name| name.to_string().to_lowercase())
+}
+
+/// Paths to font files in the given directory
+fn font_files(dir: &Path, recursive: bool) -> Vec<PathBuf> {
+    let mut out = vec![];
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            log::warn!("Failed to read font directory {}: {e}", dir.display());
+            return out;
+        }
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        // Not following symlinked directories avoids cycles
+        match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => {
+                if recursive {
+                    out.extend(font_files(&path, true));
+                }
+            }
+            Ok(_) => {
+                if has_font_extension(&path) {
+                    out.push(path);
+                }
+            }
+            Err(_) => {}
+        }
+    }
+    out.sort();
+    out
+}
+
+fn has_font_extension(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| {
+            EXTENSIONS
+                .iter()
+                .any(|known| extension.eq_ignore_ascii_case(known))
+        })
+        .unwrap_or(false)
+}
@@ -1,16 +1,28 @@
-use super::font::{Font, FontFromFileError, Metrics};
+use super::{
+    font::{Font, FontFromFileError, Metrics},
+    font_files::{self, FontIndex},
+};
 use crate::{
     assets,
     ui::options::FontSize,
     util::{vec2::Vec2, MaybeInto, Parse},
 };
 use font_kit::{error::SelectionError, handle::Handle, source::SystemSource};
-use swash::{Setting, Style, Weight};
+use std::path::PathBuf;
+use swash::{Setting, Style};
 
 /// Loaded fonts
 #[derive(Debug, Clone)]
 pub struct Fonts {
     fonts: Vec<FontFamily>,
+    /// Fonts preferred for double-width cells, set by guifontwide
+    wide: Vec<FontFamily>,
+    /// Fonts in additional directories to search for font families
+    font_index: FontIndex,
+    /// Pixels added to the line height
+    linespace: f32,
+    /// Pixels added to the cell width
+    letter_spacing: f32,
 }
 
 impl Default for Fonts {
@@ -54,54 +66,86 @@ impl Fonts {
                     bold_italic: None,
                 },
             ],
+            wide: vec![],
+            font_index: FontIndex::default(),
+            linespace: 0.,
+            letter_spacing: 0.,
         }
     }
 
     pub fn set_font_size(&mut self, size: FontSize) {
-        for font in self.fonts.iter_mut() {
+        for font in self.fonts.iter_mut().chain(self.wide.iter_mut()) {
             font.resize(size);
         }
     }
 
     pub fn set_fonts(&mut self, fonts: Vec<FontSetting>, size: FontSize) {
-        let mut old = std::mem::take(&mut self.fonts);
-        self.fonts = fonts
-            .into_iter()
-            .filter_map(move |font| {
-                if let Some(i) = old.iter().position(|old| old.setting == font) {
-                    let mut existing = old.swap_remove(i);
-                    existing.resize(size);
-                    Some(existing)
-                } else {
-                    let name = font.name.clone();
-                    match FontFamily::with_settings(font, size) {
-                        Ok(family) => Some(family),
-                        Err(e) => {
-                            log::warn!("Failed to load family {}: {e}", name);
-                            None
-                        }
-                    }
-                }
-            })
-            .collect();
+        let old = std::mem::take(&mut self.fonts);
+        self.fonts = load_families(old, fonts, size, &self.font_index);
         if self.fonts.is_empty() {
             self.fonts = Self::new().fonts;
         }
+        // Wide families are sized to match the regular fonts
+        for font in self.wide.iter_mut() {
+            font.resize(size);
+        }
+    }
+
+    /// Set the fonts to prefer for double-width cells. An empty list means
+    /// double-width cells use the same fonts as everything else.
+    pub fn set_wide_fonts(&mut self, fonts: Vec<FontSetting>, size: FontSize) {
+        let old = std::mem::take(&mut self.wide);
+        self.wide = load_families(old, fonts, size, &self.font_index);
+    }
+
+    /// Set the directories to search for font families, indexing the fonts in
+    /// them if they changed
+    pub fn set_font_dirs(&mut self, font_dirs: Vec<PathBuf>) {
+        if font_dirs != self.font_index.dirs() {
+            self.font_index = FontIndex::new(font_dirs);
+        }
+    }
+
+    /// Set the pixels to add between lines and between letters
+    pub fn set_spacing(&mut self, linespace: f32, letter_spacing: f32) {
+        self.linespace = linespace;
+        self.letter_spacing = letter_spacing;
     }
 
     pub fn families(&self) -> impl Iterator<Item = &FontFamily> {
         self.fonts.iter()
     }
 
+    /// The wide font families along with their family indices. Indices
+    /// continue after those of the regular families.
+    pub fn wide_families(&self) -> impl Iterator<Item = (usize, &FontFamily)> {
+        self.wide
+            .iter()
+            .enumerate()
+            .map(|(i, family)| (i + self.fonts.len(), family))
+    }
+
+    /// The regular or wide family with the given index
+    pub fn family(&self, index: usize) -> Option<&FontFamily> {
+        self.fonts.iter().chain(self.wide.iter()).nth(index)
+    }
+
+    /// Whether the family index refers to a wide family
+    pub fn is_wide(&self, index: usize) -> bool {
+        index >= self.fonts.len()
+    }
+
     pub fn fonts(&self) -> impl Iterator<Item = (&Font, FontStyle)> {
         self.families().flat_map(|font| font.iter())
     }
 
-    /// Get the metrics for the first loaded font
+    /// Get the metrics for the first loaded font, including line and letter
+    /// spacing
     pub fn metrics(&self) -> Metrics {
         self.families()
             .find_map(|variants| variants.metrics())
             .unwrap()
+            .with_spacing(self.linespace, self.letter_spacing)
     }
 
     /// Get the cell size of the first loaded font
@@ -110,6 +154,35 @@ impl Fonts {
     }
 }
 
+/// Load the given font families, reusing previously loaded families where the
+/// settings are unchanged
+fn load_families(
+    mut old: Vec<FontFamily>,
+    fonts: Vec<FontSetting>,
+    size: FontSize,
+    font_index: &FontIndex,
+) -> Vec<FontFamily> {
+    fonts
+        .into_iter()
+        .filter_map(move |font| {
+            if let Some(i) = old.iter().position(|old| old.setting == font) {
+                let mut existing = old.swap_remove(i);
+                existing.resize(size);
+                Some(existing)
+            } else {
+                let name = font.name.clone();
+                match FontFamily::with_settings(font, size, font_index) {
+                    Ok(family) => Some(family),
+                    Err(e) => {
+                        log::warn!("Failed to load family {}: {e}", name);
+                        None
+                    }
+                }
+            }
+        })
+        .collect()
+}
+
 #[derive(Clone, Debug)]
 pub struct FontFamily {
     /// The font name
@@ -137,9 +210,48 @@ pub enum FontFamilyError {
 }
 
 impl FontFamily {
-    /// Attempt to load the system font with the given name
-    pub fn with_settings(setting: FontSetting, size: FontSize) -> Result<Self, FontFamilyError> {
-        let family = SystemSource::new().select_family_by_name(&setting.name)?;
+    /// Attempt to load the font with the given name. The name may be a path
+    /// to a font file or directory. Otherwise, the font directories are
+    /// searched for the family before falling back to the system fonts.
+    pub fn with_settings(
+        setting: FontSetting,
+        size: FontSize,
+        font_index: &FontIndex,
+    ) -> Result<Self, FontFamilyError> {
+        let fonts = if font_files::is_path(&setting.name) {
+            font_files::load_path(&font_files::expand_home(&setting.name), size)?
+        } else {
+            let fonts = font_index.find(&setting.name, size);
+            if fonts.is_empty() {
+                Self::load_system(&setting.name, size)?
+            } else {
+                fonts
+            }
+        };
+        Self::from_fonts(setting, fonts)
+    }
+
+    fn load_system(name: &str, size: FontSize) -> Result<Vec<Font>, FontFamilyError> {
+        let family = SystemSource::new().select_family_by_name(name)?;
+        family
+            .fonts()
+            .iter()
+            .map(|font| match font {
+                Handle::Path { path, font_index } => {
+                    Ok(Font::from_file(path, *font_index as usize, size)?)
+                }
+                Handle::Memory { bytes, font_index } => {
+                    Font::from_bytes(bytes.clone(), *font_index as usize, size)
+                        .ok_or(FontFamilyError::InvalidFont)
+                }
+            })
+            .collect()
+    }
+
+    /// Sort the fonts into variants by their weight and italic flags. Where
+    /// several fonts fit a variant, the one closest to the standard regular
+    /// or bold weight is used.
+    fn from_fonts(setting: FontSetting, fonts: Vec<Font>) -> Result<Self, FontFamilyError> {
         let mut out = Self {
             setting,
             regular: None,
@@ -148,23 +260,19 @@ impl FontFamily {
             bold_italic: None,
         };
 
-        for font in family.fonts() {
-            let font = match font {
-                Handle::Path { path, font_index } => {
-                    Font::from_file(path, *font_index as usize, size)?
-                }
-                Handle::Memory { bytes, font_index } => {
-                    Font::from_bytes(bytes.clone(), *font_index as usize, size)
-                        .ok_or(FontFamilyError::InvalidFont)?
-                }
-            };
+        let mut distances = [u16::MAX; 4];
+        for font in fonts {
             let attributes = font.as_ref().attributes();
-            match (attributes.weight(), attributes.style()) {
-                (Weight::NORMAL, Style::Normal) => out.regular = Some(font),
-                (Weight::NORMAL, Style::Italic) => out.italic = Some(font),
-                (Weight::BOLD, Style::Normal) => out.bold = Some(font),
-                (Weight::BOLD, Style::Italic) => out.bold_italic = Some(font),
-                _ => {}
+            let weight = attributes.weight().0;
+            // Semibold and heavier count as bold
+            let bold = weight >= 600;
+            let italic = attributes.style() != Style::Normal;
+            let style = FontStyle::new(bold, italic);
+            let distance = weight.abs_diff(if bold { 700 } else { 400 });
+            let i = STYLES.iter().position(|&s| s == style).unwrap();
+            if distance < distances[i] {
+                distances[i] = distance;
+                *out.style_mut(style) = Some(font);
             }
         }
 
@@ -185,6 +293,15 @@ impl FontFamily {
         }
     }
 
+    fn style_mut(&mut self, style: FontStyle) -> &mut Option<Font> {
+        match style {
+            FontStyle::Regular => &mut self.regular,
+            FontStyle::Bold => &mut self.bold,
+            FontStyle::Italic => &mut self.italic,
+            FontStyle::BoldItalic => &mut self.bold_italic,
+        }
+    }
+
     /// Recalculate the font metrics for the loaded variants
     pub fn resize(&mut self, size: FontSize) {
         for (font, _) in self.iter_mut() {
@@ -213,9 +330,11 @@ impl FontFamily {
         .filter_map(|(font, style)| font.as_mut().map(|font| (font, style)))
     }
 
-    /// Metrics for the first loaded variant
+    /// Metrics for the first loaded variant, adjusted by the family settings
     pub fn metrics(&self) -> Option<Metrics> {
-        self.iter().map(|font| font.0.metrics()).next()
+        self.iter()
+            .map(|font| font.0.metrics().with_line_height(self.setting.line_height))
+            .next()
     }
 }
 
@@ -247,11 +366,19 @@ impl FontStyle {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct FontSetting {
     pub name: String,
     pub features: Vec<Setting<u16>>,
     pub variations: Vec<Setting<f32>>,
+    /// Whether to hint glyph outlines when rasterizing
+    pub hinting: bool,
+    /// Amount to thicken glyph outlines by in pixels
+    pub embolden: f32,
+    /// Multiplier of the line height given by the font metrics
+    pub line_height: f32,
+    /// Amount to raise glyphs above the baseline in pixels
+    pub baseline_offset: f32,
 }
 
 impl FontSetting {
@@ -260,6 +387,10 @@ impl FontSetting {
             name,
             features: vec![],
             variations: vec![],
+            hinting: true,
+            embolden: 0.,
+            line_height: 1.,
+            baseline_offset: 0.,
         }
     }
 }
@@ -270,21 +401,21 @@ impl Parse for FontSetting {
             rmpv::Value::String(s) => Some(Self::with_name(s.into_str()?)),
             rmpv::Value::Map(map) => {
                 let mut name = None;
-                let mut features = vec![];
-                let mut variations = vec![];
+                let mut out = Self::with_name(String::new());
                 for (k, v) in map {
                     match k.as_str()? {
                         "name" => name = Some(v.maybe_into()?),
-                        "features" => features = v.maybe_into()?,
-                        "variations" => variations = v.maybe_into()?,
+                        "features" => out.features = v.maybe_into()?,
+                        "variations" => out.variations = v.maybe_into()?,
+                        "hinting" => out.hinting = v.maybe_into()?,
+                        "embolden" => out.embolden = v.maybe_into()?,
+                        "line_height" => out.line_height = v.maybe_into()?,
+                        "baseline_offset" => out.baseline_offset = v.maybe_into()?,
                         _ => {}
                     }
                 }
-                Some(Self {
-                    name: name?,
-                    features,
-                    variations,
-                })
+                out.name = name?;
+                Some(out)
             }
             _ => None,
         }
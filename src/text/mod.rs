@@ -1,4 +1,6 @@
 pub mod atlas;
 pub mod cache;
 pub mod font;
+pub mod font_files;
 pub mod fonts;
+pub mod system_fonts;
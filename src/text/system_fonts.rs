@@ -0,0 +1,127 @@
+//! Enumerates the font families installed on the system
+
+use font_kit::{handle::Handle, source::SystemSource};
+use rmpv::Value;
+use std::{collections::HashMap, fs, path::PathBuf, sync::Arc};
+use swash::{FontRef, StringId, Style};
+
+/// An installed font family
+#[derive(Debug, Clone, PartialEq)]
+pub struct SystemFamily {
+    pub name: String,
+    pub styles: Vec<SystemStyle>,
+    /// Whether every loaded face in the family is monospace
+    pub monospace: bool,
+}
+
+/// A single face of an installed font family
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemStyle {
+    /// The style name given by the font, such as "Bold Italic"
+    pub name: String,
+    pub weight: u16,
+    pub italic: bool,
+}
+
+/// List the installed font families, sorted by name. Families whose faces
+/// cannot be read are skipped. This reads every font file, so it should not be
+/// called on the main thread.
+pub fn list() -> Vec<SystemFamily> {
+    let source = SystemSource::new();
+    let mut names = match source.all_families() {
+        Ok(names) => names,
+        Err(e) => {
+            log::error!("Failed to enumerate system fonts: {e}");
+            return vec![];
+        }
+    };
+    names.sort_by_key(|name| name.to_lowercase());
+    names.dedup();
+
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let family = source.select_family_by_name(&name).ok()?;
+            let mut files: HashMap<PathBuf, Arc<Vec<u8>>> = HashMap::new();
+            let mut styles = vec![];
+            let mut monospace = true;
+            for handle in family.fonts() {
+                let (data, index) = match handle {
+                    Handle::Path { path, font_index } => {
+                        let data = match files.get(path) {
+                            Some(data) => data.clone(),
+                            None => {
+                                let Ok(data) = fs::read(path) else {
+                                    continue;
+                                };
+                                let data = Arc::new(data);
+                                files.insert(path.clone(), data.clone());
+                                data
+                            }
+                        };
+                        (data, *font_index)
+                    }
+                    Handle::Memory { bytes, font_index } => (bytes.clone(), *font_index),
+                };
+                let Some(font) = FontRef::from_index(&data, index as usize) else {
+                    continue;
+                };
+                monospace &= font.metrics(&[]).is_monospace;
+                let attributes = font.attributes();
+                styles.push(SystemStyle {
+                    name: font
+                        .localized_strings()
+                        .find_by_id(StringId::SubFamily, None)
+                        .map(|name| name.to_string())
+                        .unwrap_or_default(),
+                    weight: attributes.weight().0,
+                    italic: attributes.style() != Style::Normal,
+                });
+            }
+
+            if styles.is_empty() {
+                None
+            } else {
+                styles.sort_by_key(|style| (style.italic, style.weight));
+                Some(SystemFamily {
+                    name,
+                    styles,
+                    monospace,
+                })
+            }
+        })
+        .collect()
+}
+
+impl From<SystemFamily> for Value {
+    fn from(family: SystemFamily) -> Self {
+        let SystemFamily {
+            name,
+            styles,
+            monospace,
+        } = family;
+        Value::Map(vec![
+            ("name".into(), name.into()),
+            (
+                "styles".into(),
+                Value::Array(styles.into_iter().map(Value::from).collect()),
+            ),
+            ("monospace".into(), monospace.into()),
+        ])
+    }
+}
+
+impl From<SystemStyle> for Value {
+    fn from(style: SystemStyle) -> Self {
+        let SystemStyle {
+            name,
+            weight,
+            italic,
+        } = style;
+        Value::Map(vec![
+            ("name".into(), name.into()),
+            ("weight".into(), weight.into()),
+            ("italic".into(), italic.into()),
+        ])
+    }
+}
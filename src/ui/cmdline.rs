@@ -1,4 +1,8 @@
-use crate::event::{CmdlineShow, CmdlineSpecialChar, Content};
+use super::HlId;
+use crate::{
+    event::{CmdlineShow, CmdlineSpecialChar, Content},
+    util::vec2::CellVec,
+};
 
 #[derive(Debug, Clone, Default)]
 pub struct Cmdline {
@@ -85,6 +89,23 @@ impl Cmdline {
         }
     }
 
+    /// Lay out the cmdline in cells, or None if it is hidden
+    pub fn layout(&self) -> Option<Layout> {
+        let mut rows = vec![];
+        let cursor = match self.mode.as_ref()? {
+            // TODO: Handle multiple levels
+            Mode::Normal { levels } => levels.last()?.layout(&mut rows),
+            Mode::Block {
+                previous_lines,
+                current_line,
+            } => {
+                rows.extend(previous_lines.iter().map(|line| line_cells(line).collect()));
+                current_line.layout(&mut rows)
+            }
+        };
+        Some(Layout { rows, cursor })
+    }
+
     pub fn special(&mut self, event: CmdlineSpecialChar) {
         let special = Some(Special::new(event.c, event.shift));
         match &mut self.mode {
@@ -122,10 +143,119 @@ pub struct Prompt {
     pub first_char: Option<char>,
     pub prompt: String,
     pub special: Option<Special>,
-    #[allow(unused)]
     pub indent: u32,
 }
 
+impl Prompt {
+    /// Add rows for the prompt and return the cursor position
+    fn layout(&self, rows: &mut Vec<Vec<Cell>>) -> CellVec<u32> {
+        let mut head = vec![Cell::new(0, self.first_char.unwrap_or(' '))];
+        head.extend(self.prompt.chars().map(|c| Cell::new(0, c)));
+        head.extend((0..self.indent).map(|_| Cell::new(0, ' ')));
+
+        // The cursor position is a byte offset into the content
+        let mut remaining = self.cursor_pos as usize;
+        let mut cursor = None;
+        for (i, line) in self.content_lines.iter().enumerate() {
+            let mut row = if i == 0 {
+                std::mem::take(&mut head)
+            } else {
+                vec![]
+            };
+            let start = row.len();
+            let mut len = 0;
+            for chunk in line.chunks.iter() {
+                for (offset, c) in chunk.text_chunk.char_indices() {
+                    if cursor.is_none() && len + offset == remaining {
+                        cursor = Some(CellVec::new(row.len() as u32, rows.len() as u32));
+                    }
+                    row.push(Cell::new(chunk.attr_id, c));
+                }
+                len += chunk.text_chunk.len();
+            }
+            if cursor.is_none() {
+                if remaining == len {
+                    cursor = Some(CellVec::new(row.len() as u32, rows.len() as u32));
+                } else if remaining > len {
+                    remaining -= len;
+                } else {
+                    // Inside a character, which should not happen
+                    cursor = Some(CellVec::new(start as u32, rows.len() as u32));
+                }
+            }
+            rows.push(row);
+        }
+        if !head.is_empty() {
+            rows.push(head);
+        }
+
+        let cursor = cursor.unwrap_or_else(|| {
+            let row = rows.len().saturating_sub(1);
+            let column = rows.get(row).map_or(0, Vec::len);
+            CellVec::new(column as u32, row as u32)
+        });
+
+        // Special characters are shown at the cursor until the rest of the
+        // sequence is typed
+        if let (Some(special), Some(row)) = (&self.special, rows.get_mut(cursor.0.y as usize)) {
+            let column = (cursor.0.x as usize).min(row.len());
+            let cell = Cell::new(0, special.c);
+            if special.shift || column == row.len() {
+                row.insert(column, cell);
+            } else {
+                row[column] = cell;
+            }
+        }
+
+        cursor
+    }
+}
+
+/// The cmdline laid out in cells. The cmdline grid and the cmdline cursor
+/// both go by this.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Layout {
+    pub rows: Vec<Vec<Cell>>,
+    /// The cursor position relative to the first row
+    pub cursor: CellVec<u32>,
+}
+
+impl Layout {
+    /// The position of the first row, such that the last row is at the bottom
+    /// of the default grid
+    pub fn origin(&self, base_grid_height: u16) -> CellVec<u32> {
+        let top = (base_grid_height as u32).saturating_sub(self.rows.len() as u32);
+        CellVec::new(0, top)
+    }
+
+    /// The position of the cursor on the default grid
+    pub fn cursor_position(&self, base_grid_height: u16) -> CellVec<u32> {
+        self.origin(base_grid_height) + self.cursor
+    }
+}
+
+/// A cell of the laid out cmdline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub highlight: HlId,
+    pub c: char,
+}
+
+impl Cell {
+    pub const fn new(highlight: HlId, c: char) -> Self {
+        Self { highlight, c }
+    }
+}
+
+fn line_cells(content: &Content) -> impl Iterator<Item = Cell> + '_ {
+    content.chunks.iter().flat_map(|chunk| {
+        chunk
+            .text_chunk
+            .chars()
+            .map(|c| Cell::new(chunk.attr_id, c))
+    })
+}
+
 impl From<CmdlineShow> for Prompt {
     fn from(value: CmdlineShow) -> Self {
         Self {
@@ -141,9 +271,7 @@ impl From<CmdlineShow> for Prompt {
 
 #[derive(Debug, Clone)]
 pub struct Special {
-    #[allow(unused)]
     pub c: char,
-    #[allow(unused)]
     pub shift: bool,
 }
 
@@ -152,3 +280,103 @@ impl Special {
         Self { c, shift }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::ContentChunk;
+
+    fn content(text: &str) -> Content {
+        Content {
+            chunks: vec![ContentChunk {
+                attr_id: 1,
+                text_chunk: text.to_string(),
+            }],
+        }
+    }
+
+    fn show(firstc: &str, prompt: &str, text: &str, pos: u32, indent: u32) -> CmdlineShow {
+        CmdlineShow {
+            content: content(text),
+            pos,
+            firstc: firstc.to_string(),
+            prompt: prompt.to_string(),
+            indent,
+            level: 1,
+        }
+    }
+
+    fn text(row: &[Cell]) -> String {
+        row.iter().map(|cell| cell.c).collect()
+    }
+
+    #[test]
+    fn normal_prompt() {
+        let mut cmdline = Cmdline::default();
+        cmdline.show(show(":", "", "echo", 2, 0));
+        let layout = cmdline.layout().unwrap();
+        assert_eq!(layout.rows.len(), 1);
+        assert_eq!(text(&layout.rows[0]), ":echo");
+        assert_eq!(layout.cursor, CellVec::new(3, 0));
+        assert_eq!(layout.cursor_position(10), CellVec::new(3, 9));
+    }
+
+    #[test]
+    fn prompt_and_indent() {
+        let mut cmdline = Cmdline::default();
+        cmdline.show(show("", "Name: ", "x", 1, 2));
+        let layout = cmdline.layout().unwrap();
+        assert_eq!(text(&layout.rows[0]), " Name:   x");
+        assert_eq!(layout.cursor, CellVec::new(10, 0));
+    }
+
+    #[test]
+    fn cursor_counts_characters_not_bytes() {
+        let mut cmdline = Cmdline::default();
+        // The cursor is after "é", which is two bytes
+        cmdline.show(show(":", "", "éa", 2, 0));
+        let layout = cmdline.layout().unwrap();
+        assert_eq!(layout.cursor, CellVec::new(2, 0));
+    }
+
+    #[test]
+    fn special_char() {
+        let mut cmdline = Cmdline::default();
+        cmdline.show(show(":", "", "ab", 1, 0));
+        cmdline.special(CmdlineSpecialChar {
+            c: '^',
+            shift: true,
+            level: 1,
+        });
+        let layout = cmdline.layout().unwrap();
+        assert_eq!(text(&layout.rows[0]), ":a^b");
+        assert_eq!(layout.cursor, CellVec::new(2, 0));
+
+        cmdline.special(CmdlineSpecialChar {
+            c: '"',
+            shift: false,
+            level: 1,
+        });
+        let layout = cmdline.layout().unwrap();
+        assert_eq!(text(&layout.rows[0]), ":a\"");
+    }
+
+    #[test]
+    fn block_mode() {
+        let mut cmdline = Cmdline::default();
+        cmdline.show(show(":", "", "function Foo()", 14, 0));
+        cmdline.show_block(vec![content(":function Foo()")]);
+        cmdline.show(show("", "", "", 0, 2));
+        cmdline.append_block(content("  echo 1"));
+        cmdline.show(show("", "", "endf", 4, 2));
+        let layout = cmdline.layout().unwrap();
+        let rows: Vec<_> = layout.rows.iter().map(|row| text(row)).collect();
+        assert_eq!(rows, [":function Foo()", "  echo 1", "   endf"]);
+        assert_eq!(layout.cursor, CellVec::new(7, 2));
+        assert_eq!(layout.origin(10), CellVec::new(0, 7));
+        assert_eq!(layout.cursor_position(10), CellVec::new(7, 9));
+
+        cmdline.hide_block();
+        assert_eq!(cmdline.layout(), None);
+    }
+}
@@ -109,12 +109,24 @@ pub struct GridContents {
     overflow: Vec<String>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Cell {
     pub text: PackedChar,
     pub highlight: HlId,
 }
 
+impl Default for Cell {
+    /// A blank cell. This is a space rather than an empty cell so that empty
+    /// cells only come from grid_line, where they follow double-width
+    /// characters.
+    fn default() -> Self {
+        Self {
+            text: PackedChar::from_char(' '),
+            highlight: 0,
+        }
+    }
+}
+
 impl GridContents {
     pub fn new() -> Self {
         Self::default()
@@ -208,6 +220,29 @@ impl GridContents {
         }
     }
 
+    /// Whether the cell at the given position holds a double-width character
+    pub fn is_wide(&self, position: CellVec<u16>) -> bool {
+        let CellVec(position) = position.cast_as::<usize>();
+        let width = self.size.0.x as usize;
+        if position.x >= width {
+            return false;
+        }
+        let i = position.y * width + position.x;
+        let next = (position.x + 1 < width)
+            .then(|| self.buffer.get(i + 1))
+            .flatten()
+            .map(|next| self.cell_contents(next));
+        self.buffer
+            .get(i)
+            .is_some_and(|cell| Self::is_wide_cell(&self.cell_contents(cell), next.as_ref()))
+    }
+
+    /// Whether a cell holds a double-width character, given the cell after it
+    /// in the row. Neovim follows a double-width character with an empty cell.
+    pub fn is_wide_cell(cell: &CellContents, next: Option<&CellContents>) -> bool {
+        !cell.is_empty() && next.is_some_and(CellContents::is_empty)
+    }
+
     /// Reset the contents of the grid
     pub fn clear(&mut self) {
         for dst in self.buffer.iter_mut() {
@@ -220,18 +255,20 @@ impl GridContents {
         &self,
     ) -> impl Iterator<Item = impl Iterator<Item = CellContents<'_>> + '_ + Clone> + '_ + Clone
     {
-        self.buffer.chunks(self.size.0.x as usize).map(|chunk| {
-            chunk.iter().map(|cell| {
-                let text = match cell.text.contents() {
-                    Contents::Char(c) => c.into(),
-                    Contents::U22(u22) => self.overflow[u22.as_u32() as usize].chars().into(),
-                };
-                CellContents {
-                    text,
-                    highlight: cell.highlight,
-                }
-            })
-        })
+        self.buffer
+            .chunks(self.size.0.x as usize)
+            .map(|chunk| chunk.iter().map(|cell| self.cell_contents(cell)))
+    }
+
+    fn cell_contents<'a>(&'a self, cell: &Cell) -> CellContents<'a> {
+        let text = match cell.text.contents() {
+            Contents::Char(c) => c.into(),
+            Contents::U22(u22) => self.overflow[u22.as_u32() as usize].chars().into(),
+        };
+        CellContents {
+            text,
+            highlight: cell.highlight,
+        }
     }
 }
 
@@ -289,3 +326,42 @@ pub struct CellContents<'a> {
     pub highlight: u32,
     pub text: OnceOrChars<'a>,
 }
+
+impl<'a> CellContents<'a> {
+    /// Whether the cell has no text, such as the cell following a
+    /// double-width character
+    pub fn is_empty(&self) -> bool {
+        matches!(self.text.clone().next(), None | Some('\0'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell(text: &str) -> grid_line::Cell {
+        grid_line::Cell {
+            text: text.to_string(),
+            hl_id: None,
+            repeat: None,
+        }
+    }
+
+    #[test]
+    fn wide_cells() {
+        let mut grid = GridContents::new();
+        grid.resize(CellVec(Vec2::new(4, 1)));
+        grid.grid_line(0, 0, vec![cell("a")]);
+        // Blank cells from a resize must not look like the empty cell after a
+        // double-width character
+        assert!(!grid.is_wide(CellVec(Vec2::new(0, 0))));
+
+        grid.grid_line(0, 1, vec![cell("界"), cell("")]);
+        assert!(grid.is_wide(CellVec(Vec2::new(1, 0))));
+        assert!(!grid.is_wide(CellVec(Vec2::new(2, 0))));
+
+        grid.clear();
+        grid.grid_line(0, 0, vec![cell("b")]);
+        assert!(!grid.is_wide(CellVec(Vec2::new(0, 0))));
+    }
+}
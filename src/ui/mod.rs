@@ -9,14 +9,14 @@ use self::{
 };
 use crate::{
     event::{
-        hl_attr_define::Attributes, mode_info_set::ModeInfo, Chdir, CmdlineBlockAppend,
-        CmdlineBlockShow, CmdlinePos, DefaultColorsSet, Event, GridClear, GridCursorGoto,
-        GridDestroy, GridLine, GridResize, GridScroll, HlGroupSet, ModeChange, ModeInfoSet,
-        MsgHistoryShow, MsgRuler, MsgSetPos, MsgShowcmd, MsgShowmode, OptionSet, PopupmenuSelect,
-        PopupmenuShow, TablineUpdate, WinClose, WinExternalPos, WinFloatPos, WinHide, WinPos,
-        WinViewport,
+        hl_attr_define::Attributes, messagepack_ext_types, mode_info_set::ModeInfo, Chdir,
+        CmdlineBlockAppend, CmdlineBlockShow, CmdlinePos, DefaultColorsSet, Event, GridClear,
+        GridCursorGoto, GridDestroy, GridLine, GridResize, GridScroll, HlGroupSet, ModeChange,
+        ModeInfoSet, MsgHistoryShow, MsgRuler, MsgSetPos, MsgShowcmd, MsgShowmode, OptionSet,
+        PopupmenuSelect, PopupmenuShow, TablineUpdate, WinClose, WinExternalPos, WinFloatPos,
+        WinHide, WinPos, WinViewport, WinViewportMargins,
     },
-    ui::window::{FloatingWindow, NormalWindow, Window},
+    ui::window::{FloatingWindow, NormalWindow, ViewportMargins, Window},
     util::vec2::{CellVec, PixelVec, Vec2},
 };
 use std::{collections::HashMap, fmt::Debug};
@@ -48,9 +48,20 @@ pub struct Ui {
     pub cursor: CursorInfo,
     /// Whether the mouse is enabled
     pub mouse: bool,
+    /// Whether to send mouse move events, from the mousemoveevent option
+    pub mousemoveevent: bool,
+    /// Whether the window under the mouse is focused automatically, from the
+    /// mousefocus option
+    pub mousefocus: bool,
+    /// Neovim window handles for the grids of normal and floating windows
+    pub window_handles: HashMap<grid::Id, messagepack_ext_types::Window>,
+    /// Margins of window grids with a winbar or border
+    pub viewport_margins: HashMap<grid::Id, ViewportMargins>,
     /// UI highlights, indexed by their ID
     // TODO: Only store the rgb_attr part
     pub highlights: Vec<Option<Attributes>>,
+    /// Hyperlink targets for highlights with the url attribute
+    pub highlight_urls: HashMap<HlId, String>,
     /// A lookup from highlight names to highlight IDs
     pub highlight_groups: HashMap<String, HlId>,
     /// Whether the highlights changed since the last flush
@@ -61,6 +72,11 @@ pub struct Ui {
     pub modes: Vec<ModeInfo>,
     /// UI options set by the option_set event
     pub guifont_update: Option<GuiFont>,
+    /// Set when the guifontwide option changes. Font sizes are ignored in
+    /// favor of those from guifont.
+    pub guifontwide_update: Option<GuiFont>,
+    /// Set when the linespace option changes
+    pub linespace_update: Option<u32>,
     /// Default highlight colors
     pub default_colors: DefaultColorsSet,
     /// Manages ext_hlstate events
@@ -78,7 +94,12 @@ pub struct Ui {
 
 impl Ui {
     pub fn new() -> Self {
-        Self::default()
+        Self {
+            // Enabled until Neovim says otherwise, as with the default value
+            // of the mouse option
+            mouse: true,
+            ..Self::default()
+        }
     }
 
     /// Index of the grid with the given id, or else the index where the
@@ -120,6 +141,8 @@ impl Ui {
         self.did_highlights_change = false;
         self.did_flush = false;
         self.guifont_update = None;
+        self.guifontwide_update = None;
+        self.linespace_update = None;
         self.ignore_next_scroll = false;
         self.messages.dirty = false;
         for grid in self.grids.iter_mut() {
@@ -132,6 +155,10 @@ impl Ui {
         match event {
             Event::OptionSet(event) => match event {
                 OptionSet::Guifont(s) if !s.is_empty() => self.guifont_update = Some(s.into()),
+                OptionSet::Guifontwide(s) => self.guifontwide_update = Some(s.into()),
+                OptionSet::Linespace(linespace) => self.linespace_update = Some(linespace),
+                OptionSet::Mousemoveevent(enabled) => self.mousemoveevent = enabled,
+                OptionSet::Mousefocus(enabled) => self.mousefocus = enabled,
                 _ => {}
             },
             Event::DefaultColorsSet(event) => {
@@ -145,6 +172,10 @@ impl Ui {
                     self.highlights.resize(i * 2, None);
                 }
                 self.highlights.insert(i, Some(event.rgb_attr));
+                match event.url {
+                    Some(url) => self.highlight_urls.insert(event.id, url),
+                    None => self.highlight_urls.remove(&event.id),
+                };
             }
             Event::HlGroupSet(HlGroupSet { name, hl_id }) => {
                 self.did_highlights_change = true;
@@ -206,12 +237,13 @@ impl Ui {
 
             Event::WinPos(WinPos {
                 grid,
-                win: _,
+                win,
                 start_row,
                 start_col,
                 width,
                 height,
             }) => {
+                self.window_handles.insert(grid, win);
                 self.show_normal(grid);
                 *self
                     .grid_mut(grid)
@@ -223,7 +255,7 @@ impl Ui {
             }
             Event::WinFloatPos(WinFloatPos {
                 grid,
-                win: _,
+                win,
                 anchor,
                 anchor_grid,
                 anchor_row,
@@ -231,6 +263,7 @@ impl Ui {
                 focusable,
                 zindex,
             }) => {
+                self.window_handles.insert(grid, win);
                 self.show_float(DrawItem::new(grid, zindex));
                 *self
                     .grid_mut(grid)
@@ -253,6 +286,8 @@ impl Ui {
             }
             Event::WinClose(WinClose { grid }) => {
                 self.hide(grid);
+                self.window_handles.remove(&grid);
+                self.viewport_margins.remove(&grid);
                 // It seems like we shouldn't be able to receive this event
                 // when a grid doesn't exist, but I have had this happen when
                 // opening DAP UI.
@@ -276,7 +311,25 @@ impl Ui {
                         .scroll_delta = scroll_delta;
                 }
             }
-            Event::WinViewportMargins(_) | Event::WinExtmark(_) => {}
+            Event::WinViewportMargins(WinViewportMargins {
+                grid,
+                win: _,
+                top,
+                bottom,
+                left,
+                right,
+            }) => {
+                self.viewport_margins.insert(
+                    grid,
+                    ViewportMargins {
+                        top,
+                        bottom,
+                        left,
+                        right,
+                    },
+                );
+            }
+            Event::WinExtmark(_) => {}
 
             Event::PopupmenuShow(event) => self.popupmenu = Some(event),
             Event::PopupmenuSelect(PopupmenuSelect { selected }) => {
@@ -388,6 +441,8 @@ impl Ui {
         if let Ok(i) = self.grids.binary_search_by(|probe| probe.id.cmp(&grid)) {
             self.grids.remove(i);
         }
+        self.window_handles.remove(&grid);
+        self.viewport_margins.remove(&grid);
         self.hide(grid);
     }
 
@@ -1,16 +1,26 @@
+use crate::text::font_files;
+
 impl From<String> for GuiFont {
     fn from(option: String) -> Self {
         let mut out = GuiFont::default();
         let mut state = ParseState::Normal;
         let mut current = String::new();
-        for c in option.chars() {
+        // Whether the current font is a path, which is taken as is
+        let mut literal = false;
+        for (i, c) in option.char_indices() {
             match state {
                 ParseState::Normal => {
-                    if c.is_whitespace() && current.is_empty() {
-                        continue;
+                    if current.is_empty() {
+                        if c.is_whitespace() {
+                            continue;
+                        }
+                        literal = font_files::is_path(entry_name(&option[i..]));
                     }
 
                     match c {
+                        // Keep the colon after a Windows drive letter
+                        ':' if literal && current.len() == 1 => current.push(c),
+                        '\\' | '_' if literal => current.push(c),
                         '\\' => state = ParseState::Escape,
                         ',' => {
                             out.fonts.push(current);
@@ -23,7 +33,10 @@ impl From<String> for GuiFont {
                     }
                 }
 
-                ParseState::Escape => current.push(c),
+                ParseState::Escape => {
+                    current.push(c);
+                    state = ParseState::Normal;
+                }
 
                 ParseState::OptionStart => {
                     state = match c {
@@ -66,6 +79,9 @@ impl From<String> for GuiFont {
             }
         }
 
+        if let ParseState::OptionSize(size, kind) = state {
+            out.size = FontSize::new(size as f32, kind);
+        }
         if !current.is_empty() {
             out.fonts.push(current);
         }
@@ -74,6 +90,16 @@ impl From<String> for GuiFont {
     }
 }
 
+/// The font name at the start of the text, before any options or other fonts
+fn entry_name(text: &str) -> &str {
+    let entry = text.split(',').next().unwrap_or_default();
+    let start = if font_files::has_drive(entry) { 2 } else { 0 };
+    match entry[start..].find(':') {
+        Some(i) => &entry[..start + i],
+        None => entry,
+    }
+}
+
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct GuiFont {
     pub fonts: Vec<String>,
@@ -120,3 +146,45 @@ enum SizeKind {
     Width,
     Height,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(option: &str) -> GuiFont {
+        GuiFont::from(option.to_string())
+    }
+
+    #[test]
+    fn family_names() {
+        let font = parse("Fira_Code:h14, Noto\\ Sans\\,Mono:w8");
+        assert_eq!(font.fonts, ["Fira Code", "Noto Sans,Mono"]);
+        assert_eq!(font.size, FontSize::Width(8.));
+    }
+
+    #[test]
+    fn paths() {
+        let cases = [
+            ("~/fonts/My_Font.ttf:h12", "~/fonts/My_Font.ttf"),
+            ("/usr/share/fonts/a_b.otf", "/usr/share/fonts/a_b.otf"),
+            ("C:\\Fonts\\My_Font.ttf:h12", "C:\\Fonts\\My_Font.ttf"),
+            ("D:/Fonts/My_Font.ttf", "D:/Fonts/My_Font.ttf"),
+            ("My_Font.ttf:h12", "My_Font.ttf"),
+            (
+                "\\\\server\\fonts\\My_Font.ttf",
+                "\\\\server\\fonts\\My_Font.ttf",
+            ),
+        ];
+        for (option, path) in cases {
+            assert_eq!(parse(option).fonts, [path], "{option}");
+        }
+        assert_eq!(parse("~/fonts/My_Font.ttf:h12").size, FontSize::Height(12.));
+    }
+
+    #[test]
+    fn paths_with_families() {
+        let font = parse("C:\\Fonts\\My_Font.ttf:h10,Fallback_Font");
+        assert_eq!(font.fonts, ["C:\\Fonts\\My_Font.ttf", "Fallback Font"]);
+        assert_eq!(font.size, FontSize::Height(10.));
+    }
+}
@@ -65,3 +65,23 @@ pub struct NormalWindow {
     pub start: CellVec<u16>,
     pub size: CellVec<u16>,
 }
+
+/// The parts of a window grid outside the viewport, such as the winbar and
+/// floating window borders, in cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ViewportMargins {
+    pub top: u32,
+    pub bottom: u32,
+    pub left: u32,
+    pub right: u32,
+}
+
+impl ViewportMargins {
+    /// Whether the position within the grid of the given size is in the
+    /// margins rather than the viewport
+    pub fn contains(&self, position: CellVec<u32>, grid_size: CellVec<u32>) -> bool {
+        let CellVec(Vec2 { x, y }) = position;
+        let CellVec(size) = grid_size;
+        y < self.top || x < self.left || y + self.bottom >= size.y || x + self.right >= size.x
+    }
+}
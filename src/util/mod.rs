@@ -93,17 +93,6 @@ pub fn srgb(c: u8) -> f32 {
     }
 }
 
-pub fn nice_s_curve(t: f32, length: f32) -> f32 {
-    let t = normalize(t, length);
-    let v = 1. - t;
-    1. - v * v
-}
-
-fn normalize(t: f32, length: f32) -> f32 {
-    let length = length.sqrt() + length.ln_1p();
-    (t / length).clamp(0., 1.)
-}
-
 #[allow(unused)]
 macro_rules! time_execution {
     ($e:expr) => {{